@@ -1,11 +1,17 @@
+// Standalone scratch binary for eyeballing what the lexer produces for
+// test.psl - it only ever needed `lexer`, so removing the dead `ast` module
+// elsewhere in the crate doesn't leave anything here to clean up.
 mod lexer;
 use lexer::tokenize;
 use std::fs;
 
 fn main() {
     let source = fs::read_to_string("test.psl").unwrap();
-    let tokens = tokenize(&source).unwrap();
-    for (i, token) in tokens.iter().enumerate() {
-        println!("{}: {:?}", i, token);
+    let result = tokenize(&source);
+    for (i, (token, span)) in result.tokens.iter().enumerate() {
+        println!("{}: {:?} ({})", i, token, span);
+    }
+    for err in &result.errors {
+        println!("error: {}", err);
     }
 }
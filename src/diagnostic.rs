@@ -0,0 +1,75 @@
+// A small, shared error type for the lexer/parser/evaluator pipeline.
+// Before this, failures at every stage were bare `String`s with no location,
+// so a user couldn't tell which tag in a large file had gone wrong. A
+// `Diagnostic` carries the `[start, end)` char-offset range of the offending
+// source text and knows how to render it like a compiler error: the source
+// line plus a `^^^` underline beneath the span.
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>, start: usize, end: usize) -> Self {
+        Diagnostic { message: message.into(), start, end }
+    }
+
+    /// Render this diagnostic against `source`, Rust-compiler style:
+    ///
+    /// ```text
+    /// error: Unknown operation: 'frobnicate'
+    ///   --> line 3, col 1
+    /// [frobnicate: item]
+    /// ^^^^^^^^^^^^^^^^^^
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let (line_no, col_no, line_text) = locate(source, self.start);
+        let underline_len = self.end.saturating_sub(self.start).max(1);
+        format!(
+            "error: {}\n  --> line {}, col {}\n{}\n{}{}",
+            self.message,
+            line_no,
+            col_no,
+            line_text,
+            " ".repeat(col_no.saturating_sub(1)),
+            "^".repeat(underline_len)
+        )
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at offset {}..{}", self.message, self.start, self.end)
+    }
+}
+
+/// Walk `source` one char at a time, counting newlines, until `offset`
+/// chars have been consumed. Returns the 1-based (line, col) of that offset
+/// along with the text of the line it falls on, for the diagnostic snippet.
+/// Offsets are char counts (matching `Lexer`'s `offset` field), not byte
+/// indices, so this can't just slice `source` directly.
+fn locate(source: &str, offset: usize) -> (usize, usize, &str) {
+    let mut line_no = 1;
+    let mut col_no = 1;
+    let mut line_start_byte = 0;
+
+    for (char_idx, (byte_idx, ch)) in source.char_indices().enumerate() {
+        if char_idx == offset {
+            break;
+        }
+        if ch == '\n' {
+            line_no += 1;
+            col_no = 1;
+            line_start_byte = byte_idx + ch.len_utf8();
+        } else {
+            col_no += 1;
+        }
+    }
+
+    let line_text = source[line_start_byte..].lines().next().unwrap_or("");
+    (line_no, col_no, line_text)
+}
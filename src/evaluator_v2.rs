@@ -1,4 +1,5 @@
-use crate::tag::{TagNode, Value};
+use crate::diagnostic::Diagnostic;
+use crate::tag::{PrimitiveKind, TagNode, Value};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
@@ -11,14 +12,15 @@ lazy_static! {
         let mut map = HashMap::new();
         map.insert("root", Evaluator::handle_root as Handler);
         map.insert("character", Evaluator::handle_character as Handler);
-        // Note: "define" is handled specially in evaluate_tag, not through handlers
-        map.insert("list", Evaluator::handle_list as Handler);
+        // Note: "define" and "list" are handled specially in evaluate_tag, not through handlers
         map.insert("text", Evaluator::handle_text as Handler);
         map.insert("number", Evaluator::handle_number as Handler);
         map.insert("flag", Evaluator::handle_flag as Handler);
         map.insert("item", Evaluator::handle_item as Handler);
         // Note: "set" is handled specially in evaluate_tag, not through handlers
         map.insert("attribute", Evaluator::handle_attribute as Handler);
+        map.insert("length", Evaluator::handle_length as Handler);
+        // Note: "index" is handled specially in evaluate_tag, not through handlers
         map
     };
 }
@@ -70,44 +72,47 @@ impl Evaluator {
                 prim.as_text()
                     .ok_or_else(|| "Operation name must be text".to_string())
             }
-            TagNode::Composite { ltag: inner_ltag, rtag: _inner_rtag } => {
+            TagNode::Composite { ltag: inner_ltag, rtag: _inner_rtag, .. } => {
                 // Walk the ltag chain to find the innermost primitive
                 self.extract_operation_name(inner_ltag)
             }
+            TagNode::Error { message, .. } => Err(message.clone()),
+            TagNode::FlatList { .. } => Err("Operation name must be text".to_string()),
         }
     }
 
     /// Execute a define block: push scope, execute content, pop scope
-    fn handle_define_block(&mut self, content: &TagNode) -> Result<Value, String> {
+    fn handle_define_block(&mut self, content: &TagNode) -> Result<Value, Diagnostic> {
         // Push a new frame for this define block
         self.frames.push(Frame::new());
-        
+
         // Execute the content within the new scope
         let result = self.evaluate_tag(content)?;
-        
+
         // Pop the frame when done
         self.frames.pop();
-        
+
         Ok(result)
     }
 
     /// Execute a set block: the ltag is [set: target], rtag is the value
     /// We need to extract the target from the set expression
-    fn handle_set_block(&mut self, set_expr: &TagNode, value_tag: &TagNode) -> Result<Value, String> {
+    fn handle_set_block(&mut self, set_expr: &TagNode, value_tag: &TagNode) -> Result<Value, Diagnostic> {
         // set_expr is [set: target], so we need to extract the rtag (target)
         let target = match set_expr {
-            TagNode::Composite { ltag: _, rtag } => rtag.as_ref(),
+            TagNode::Composite { ltag: _, rtag, .. } => rtag.as_ref(),
             _ => {
-                return Err("set expression must be composite [set: target]".to_string());
+                let span = set_expr.span();
+                return Err(Diagnostic::new("set expression must be composite [set: target]", span.0, span.1));
             }
         };
-        
+
         // Evaluate the target to get a reference
         let target_value = self.evaluate_tag(target)?;
-        
+
         // Evaluate the value to assign
         let value = self.evaluate_tag(value_tag)?;
-        
+
         // Perform the assignment
         if let Value::Reference(name) = target_value {
             // Search up the frame stack to find where this is defined
@@ -121,12 +126,94 @@ impl Evaluator {
                     return Ok(value);
                 }
             }
-            Err(format!("Cannot assign to undefined attribute/variable '{}'", name))
+            let span = target.span();
+            Err(Diagnostic::new(format!("Cannot assign to undefined attribute/variable '{}'", name), span.0, span.1))
         } else {
-            Err(format!("set target must resolve to a reference, got {:?}", target_value))
+            let span = target.span();
+            Err(Diagnostic::new(format!("set target must resolve to a reference, got {:?}", target_value), span.0, span.1))
+        }
+    }
+
+    /// Execute a list block: rtag is the `[tag1: [tag2: [... : item]]]`
+    /// chain `StreamingParser::create_list_node` builds. Evaluate each
+    /// element and collect them into a single `Value::List`.
+    fn handle_list_block(&mut self, rtag: &TagNode) -> Result<Value, Diagnostic> {
+        let mut items = Vec::new();
+        self.collect_list_items(rtag, &mut items)?;
+        Ok(Value::List(items))
+    }
+
+    /// Walk a list's chain: the `item` keyword marks the end, otherwise
+    /// `ltag` is the next element and `rtag` is the rest of the chain.
+    fn collect_list_items(&mut self, node: &TagNode, items: &mut Vec<Value>) -> Result<(), Diagnostic> {
+        if let TagNode::Primitive(prim) = node {
+            if matches!(&prim.kind, PrimitiveKind::Keyword(kw) if kw == "item") {
+                return Ok(());
+            }
+        }
+
+        match node {
+            TagNode::Composite { ltag, rtag, .. } => {
+                let value = self.evaluate_tag(ltag)?;
+                items.push(value);
+                self.collect_list_items(rtag, items)
+            }
+            TagNode::Primitive(_) => {
+                // create_list_node always terminates with the `item`
+                // keyword, so this shouldn't happen - but evaluate it as a
+                // trailing element rather than silently dropping it.
+                let value = self.evaluate_tag(node)?;
+                items.push(value);
+                Ok(())
+            }
+            TagNode::Error { span, message } => {
+                Err(Diagnostic::new(message.clone(), span.0, span.1))
+            }
+            // ParserConfig::flat_tree: the chain is a real Vec, so just
+            // evaluate each item in order instead of walking cons cells.
+            TagNode::FlatList { items: flat_items, .. } => {
+                for item in flat_items {
+                    let value = self.evaluate_tag(item)?;
+                    items.push(value);
+                }
+                Ok(())
+            }
         }
     }
 
+    /// Execute an index block: ltag is `index`, rtag is `[list: idx]` - a
+    /// composite pairing the list expression with the index expression, the
+    /// same way `handle_set_block`'s `[set: target]` pairs an operation
+    /// with a structural argument instead of a single evaluated `Value`.
+    /// Needs the un-evaluated `TagNode` (not just the handler registry's
+    /// `&Value`) so both sides can be evaluated independently and blamed
+    /// separately on failure.
+    fn handle_index_block(&mut self, rtag: &TagNode) -> Result<Value, Diagnostic> {
+        let (list_tag, index_tag) = match rtag {
+            TagNode::Composite { ltag, rtag, .. } => (ltag.as_ref(), rtag.as_ref()),
+            _ => {
+                let span = rtag.span();
+                return Err(Diagnostic::new("index expects [list: index]", span.0, span.1));
+            }
+        };
+
+        let list_value = self.evaluate_tag(list_tag)?;
+        let index_value = self.evaluate_tag(index_tag)?;
+
+        let idx = match index_value {
+            Value::Number(n) if n >= 0.0 && n.fract() == 0.0 => n as usize,
+            _ => {
+                let span = index_tag.span();
+                return Err(Diagnostic::new(format!("index must be a non-negative integer, got {:?}", index_value), span.0, span.1));
+            }
+        };
+
+        list_value.get(idx).cloned().ok_or_else(|| {
+            let span = rtag.span();
+            Diagnostic::new(format!("index {} out of bounds for list of length {:?}", idx, list_value.len()), span.0, span.1)
+        })
+    }
+
     fn writeln_log(&mut self, msg: &str) -> std::io::Result<()> {
         if let Some(ref mut file) = self.log_file {
             writeln!(file, "{}", msg)?;
@@ -135,64 +222,115 @@ impl Evaluator {
         Ok(())
     }
 
-    pub fn validate(&self, root: &TagNode) -> Result<(), String> {
+    pub fn validate(&self, root: &TagNode) -> Result<(), Diagnostic> {
         self.validate_tag(root, &std::collections::HashSet::new())
     }
 
-    fn validate_tag(&self, tag: &TagNode, _scope: &std::collections::HashSet<String>) -> Result<(), String> {
+    fn validate_tag(&self, tag: &TagNode, _scope: &std::collections::HashSet<String>) -> Result<(), Diagnostic> {
         match tag {
             TagNode::Primitive(_) => Ok(()),
-            TagNode::Composite { ltag, rtag } => {
+            TagNode::Composite { ltag, rtag, span } => {
+                // A "list" tag's rtag is create_list_node's cons-chain of
+                // elements, not a tag tree - its links are plain `ltag:
+                // element, rtag: restOfChain` pairs with no operation of
+                // their own, so walking them through the generic composite
+                // case below would check "is this element's text a known
+                // operation" for every element and reject e.g. `"c"`. Each
+                // element still gets fully validated, the same way
+                // `collect_list_items`/`evaluate_tag` evaluate each one.
+                if self.extract_operation_name(ltag).as_deref() == Ok("list") {
+                    return self.validate_list_items(rtag, _scope);
+                }
+
                 // Validate both sides
                 self.validate_tag(ltag, _scope)?;
                 self.validate_tag(rtag, _scope)?;
-                
+
                 // Check operation is valid (extract operation name from ltag, handling nesting)
                 if let Ok(op_name) = self.extract_operation_name(ltag) {
-                    // "define" and "set" are handled specially, not through HANDLERS registry
-                    if op_name != "define" && op_name != "set" && !HANDLERS.contains_key(op_name.as_str()) {
-                        return Err(format!("Unknown operation: '{}'", op_name));
+                    // "define", "set", "list" and "index" are handled specially, not through HANDLERS registry
+                    if op_name != "define" && op_name != "set" && op_name != "list" && op_name != "index" && !HANDLERS.contains_key(op_name.as_str()) {
+                        return Err(Diagnostic::new(format!("Unknown operation: '{}'", op_name), span.0, span.1));
                     }
                 }
                 Ok(())
             }
+            // A parse-recovery placeholder: surface the same failure the
+            // parser already recorded, rather than treating it as valid.
+            TagNode::Error { span, message } => {
+                Err(Diagnostic::new(message.clone(), span.0, span.1))
+            }
+            TagNode::FlatList { items, .. } => {
+                for item in items {
+                    self.validate_tag(item, _scope)?;
+                }
+                Ok(())
+            }
         }
     }
 
-    pub fn execute_root(&mut self, root: &TagNode) -> Result<Value, String> {
+    /// Validate a list's cons-chain (mirrors `collect_list_items`'s walk):
+    /// each link's `ltag` is an element (validated as an ordinary tag,
+    /// since it may itself be a nested composite) and `rtag` is the rest
+    /// of the chain, terminated by the `item` keyword.
+    fn validate_list_items(&self, node: &TagNode, scope: &std::collections::HashSet<String>) -> Result<(), Diagnostic> {
+        if let TagNode::Primitive(prim) = node {
+            if matches!(&prim.kind, PrimitiveKind::Keyword(kw) if kw == "item") {
+                return Ok(());
+            }
+        }
+
+        match node {
+            TagNode::Composite { ltag, rtag, .. } => {
+                self.validate_tag(ltag, scope)?;
+                self.validate_list_items(rtag, scope)
+            }
+            TagNode::Primitive(_) => self.validate_tag(node, scope),
+            TagNode::Error { span, message } => Err(Diagnostic::new(message.clone(), span.0, span.1)),
+            TagNode::FlatList { items, .. } => {
+                for item in items {
+                    self.validate_tag(item, scope)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn execute_root(&mut self, root: &TagNode) -> Result<Value, Diagnostic> {
         self.writeln_log("=== Validation ===\n")
-            .map_err(|e| format!("Log error: {}", e))?;
-        
+            .map_err(|e| Diagnostic::new(format!("Log error: {}", e), 0, 0))?;
+
         self.validate(root)?;
-        
+
         self.writeln_log("=== Evaluation Trace ===\n")
-            .map_err(|e| format!("Log error: {}", e))?;
+            .map_err(|e| Diagnostic::new(format!("Log error: {}", e), 0, 0))?;
 
         let result = self.evaluate_tag(root)?;
 
         self.writeln_log("\n=== Evaluation Complete ===")
-            .map_err(|e| format!("Log error: {}", e))?;
+            .map_err(|e| Diagnostic::new(format!("Log error: {}", e), 0, 0))?;
 
         Ok(result)
     }
 
-    pub fn evaluate_tags(&mut self, tags: &[TagNode]) -> Result<(), String> {
+    pub fn evaluate_tags(&mut self, tags: &[TagNode]) -> Result<(), Diagnostic> {
         self.writeln_log("=== Evaluation Trace ===\n")
-            .map_err(|e| format!("Log error: {}", e))?;
+            .map_err(|e| Diagnostic::new(format!("Log error: {}", e), 0, 0))?;
 
         for tag in tags {
             self.evaluate_tag(tag)?;
         }
 
         self.writeln_log("\n=== Evaluation Complete ===")
-            .map_err(|e| format!("Log error: {}", e))?;
+            .map_err(|e| Diagnostic::new(format!("Log error: {}", e), 0, 0))?;
 
         Ok(())
     }
 
-    pub fn evaluate_tag(&mut self, tag: &TagNode) -> Result<Value, String> {
+    pub fn evaluate_tag(&mut self, tag: &TagNode) -> Result<Value, Diagnostic> {
         self.eval_counter += 1;
         let eval_id = self.eval_counter;
+        let span = tag.span();
 
         match tag {
             TagNode::Primitive(prim) => {
@@ -203,21 +341,22 @@ impl Evaluator {
                     prim.as_display_string(),
                     value
                 ))
-                .map_err(|e| format!("Log error: {}", e))?;
+                .map_err(|e| Diagnostic::new(format!("Log error: {}", e), span.0, span.1))?;
                 Ok(value)
             }
-            TagNode::Composite { ltag, rtag } => {
+            TagNode::Composite { ltag, rtag, .. } => {
                 self.writeln_log(&format!("[Eval {}] Composite tag: [ltag: rtag]", eval_id))
-                    .map_err(|e| format!("Log error: {}", e))?;
+                    .map_err(|e| Diagnostic::new(format!("Log error: {}", e), span.0, span.1))?;
 
                 // Extract operation name from ltag (walk to innermost primitive if ltag is composite)
-                let op_name = self.extract_operation_name(ltag)?;
+                let op_name = self.extract_operation_name(ltag)
+                    .map_err(|msg| Diagnostic::new(msg, span.0, span.1))?;
 
                 self.writeln_log(&format!(
                     "[Eval {}] Operation: {}",
                     eval_id, op_name
                 ))
-                .map_err(|e| format!("Log error: {}", e))?;
+                .map_err(|e| Diagnostic::new(format!("Log error: {}", e), span.0, span.1))?;
 
                 // Dispatch based on operation - some ops need special handling
                 let result = match op_name.as_str() {
@@ -229,24 +368,46 @@ impl Evaluator {
                         // set needs special handling - it evaluates the target (ltag) and assigns to it
                         self.handle_set_block(ltag, rtag)?
                     }
+                    "list" => {
+                        // list needs special handling - its rtag is a chain of elements,
+                        // not a single value to dispatch through HANDLERS
+                        self.handle_list_block(rtag)?
+                    }
+                    "index" => {
+                        // index needs special handling - its rtag is [list: idx],
+                        // a structural pair to evaluate, not a single value
+                        self.handle_index_block(rtag)?
+                    }
                     _ => {
                         // For other operations, evaluate rtag normally and dispatch
                         self.writeln_log(&format!("[Eval {}] Evaluating rtag...", eval_id))
-                            .map_err(|e| format!("Log error: {}", e))?;
+                            .map_err(|e| Diagnostic::new(format!("Log error: {}", e), span.0, span.1))?;
                         let rtag_value = self.evaluate_tag(rtag)?;
 
                         self.writeln_log(&format!("[Eval {}]   rtag evaluated to: {}", eval_id, rtag_value))
-                            .map_err(|e| format!("Log error: {}", e))?;
+                            .map_err(|e| Diagnostic::new(format!("Log error: {}", e), span.0, span.1))?;
 
-                        self.execute_operation(&op_name, &rtag_value)?
+                        self.execute_operation(&op_name, &rtag_value)
+                            .map_err(|msg| Diagnostic::new(msg, span.0, span.1))?
                     }
                 };
 
                 self.writeln_log(&format!("[Eval {}] Handler result: {}", eval_id, result))
-                    .map_err(|e| format!("Log error: {}", e))?;
+                    .map_err(|e| Diagnostic::new(format!("Log error: {}", e), span.0, span.1))?;
 
                 Ok(result)
             }
+            // A parse-recovery placeholder: evaluation can't proceed past
+            // it, so surface the same failure the parser already recorded.
+            TagNode::Error { message, .. } => {
+                Err(Diagnostic::new(message.clone(), span.0, span.1))
+            }
+            // Only valid as the rtag of a `list` tag, where
+            // `handle_list_block`/`collect_list_items` handle it directly -
+            // reaching here means it showed up somewhere else in the tree.
+            TagNode::FlatList { .. } => {
+                Err(Diagnostic::new("Unexpected flat list outside a list tag", span.0, span.1))
+            }
         }
     }
 
@@ -279,7 +440,17 @@ impl Evaluator {
     fn handle_root(&mut self, rtag: &Value) -> Result<Value, String> {
         // Root just executes its content (the implicit list)
         // The actual execution happens through normal tag evaluation
-        Ok(rtag.clone())
+        //
+        // `create_root` wraps *every* program in a synthetic `[list: ...]`,
+        // even a single ordinary tag - so a one-element list here just means
+        // "a program with one top-level tag" and should surface as that
+        // tag's own value, not a one-item `Value::List`. A program that
+        // genuinely has zero or several top-level tags still comes through
+        // as a list.
+        match rtag {
+            Value::List(items) if items.len() == 1 => Ok(items[0].clone()),
+            _ => Ok(rtag.clone()),
+        }
     }
 
     fn handle_character(&mut self, rtag: &Value) -> Result<Value, String> {
@@ -291,12 +462,6 @@ impl Evaluator {
         }
     }
 
-    fn handle_list(&mut self, _rtag: &Value) -> Result<Value, String> {
-        // List processing happens at the streaming parser level
-        // Handlers just acknowledge it
-        Ok(Value::Item)
-    }
-
     fn handle_text(&mut self, rtag: &Value) -> Result<Value, String> {
         Ok(rtag.clone())
     }
@@ -313,20 +478,80 @@ impl Evaluator {
         Ok(Value::Item)
     }
 
+    fn handle_length(&mut self, rtag: &Value) -> Result<Value, String> {
+        rtag.len()
+            .map(|n| Value::Number(n as f64))
+            .ok_or_else(|| format!("length expects a list, got {:?}", rtag))
+    }
+
     fn handle_attribute(&mut self, rtag: &Value) -> Result<Value, String> {
         // attribute both declares (if needed in current scope) and returns a reference
         if let Value::Text(name) = rtag {
             // Check if attribute exists in any scope first
             let exists = self.frames.iter().any(|f| f.attributes.contains_key(name));
-            
+
             // If not found in any scope, declare it in current frame
             if !exists {
                 self.current_frame().attributes.insert(name.clone(), Value::Item);
             }
-            
+
             Ok(Value::Reference(name.clone()))
         } else {
             Err("Attribute name must be text".to_string())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::pipeline::interpret;
+    use crate::tag::Value;
+
+    #[test]
+    fn length_of_a_list_counts_its_elements() {
+        let (value, _store) = interpret(r#"[length: [list*: ["a", "b", "c"]]]"#)
+            .expect("valid program should evaluate");
+        assert_eq!(value, Value::Number(3.0));
+    }
+
+    #[test]
+    fn length_of_a_non_list_is_an_error() {
+        let err = interpret("[length: [number: 1]]").unwrap_err();
+        assert!(err.message.contains("length expects a list"));
+    }
+
+    #[test]
+    fn index_looks_up_an_element_by_position() {
+        let (value, _store) = interpret(r#"[index: [[list*: ["a", "b", "c"]]: 1]]"#)
+            .expect("valid program should evaluate");
+        assert_eq!(value, Value::Text("b".to_string()));
+    }
+
+    #[test]
+    fn index_out_of_bounds_is_an_error() {
+        let err = interpret(r#"[index: [[list*: ["a", "b", "c"]]: 5]]"#).unwrap_err();
+        assert!(err.message.contains("out of bounds"));
+    }
+
+    #[test]
+    fn index_with_a_negative_or_fractional_number_is_an_error() {
+        let err = interpret(r#"[index: [[list*: ["a", "b", "c"]]: 1.5]]"#).unwrap_err();
+        assert!(err.message.contains("non-negative integer"));
+        let err = interpret(r#"[index: [[list*: ["a", "b", "c"]]: -1]]"#).unwrap_err();
+        assert!(err.message.contains("non-negative integer"));
+    }
+
+    #[test]
+    fn list_elements_are_validated_without_being_mistaken_for_operations() {
+        // Regression case: validate_tag's generic Composite branch treats an
+        // ltag's text as an operation name, which would previously reject
+        // `"c"` as an "Unknown operation" when walking a list's cons-chain
+        // instead of recognizing it as a plain element via validate_list_items.
+        let (value, _store) = interpret(r#"[list*: ["a", "b", "c"]]"#)
+            .expect("valid program should evaluate");
+        match value {
+            Value::List(items) => assert_eq!(items.len(), 3),
+            other => panic!("expected a list value, got {:?}", other),
+        }
+    }
+}
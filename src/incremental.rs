@@ -0,0 +1,269 @@
+// ============================================================================
+// INCREMENTAL REPARSING - editor/LSP support
+// ============================================================================
+// An editor reports edits as "replace this char range with this text", one
+// keystroke at a time. Running the full tokenize -> parse pipeline on every
+// keystroke is O(file) per edit, which is fine for a one-shot CLI run but
+// not for keeping up with typing in a large document. `reparse` instead
+// walks the existing tree down to the smallest `Composite` whose bracket
+// pair fully contains the edit, re-parses only the text under that node,
+// and splices the result back in - O(edited tag) instead of O(file), same
+// idea as rust-analyzer's reparsing pass.
+use std::ops::Range;
+
+use crate::lexer::tokenize;
+use crate::streaming_parser::StreamingParser;
+use crate::tag::TagNode;
+
+/// Reparse `old_tree` (parsed from `old_source`) after an edit that
+/// replaces the char range `edit` of `old_source` with `replacement`.
+///
+/// Finds the smallest `Composite` in `old_tree` whose span fully contains
+/// `edit`, re-lexes and re-parses just the post-edit text covering that
+/// node, and splices the resulting subtree back in, shifting the spans of
+/// every node that falls after the edit by the length delta so the whole
+/// tree's offsets stay valid against the new source. Falls back to
+/// `full_reparse` of the entire new source when no enclosing node contains
+/// the edit, when that node spans the whole program (a lone top-level tag
+/// shares its span with the implicit root wrapper), or when re-parsing that
+/// node's slice doesn't yield a single, cleanly-parsed tag - e.g. the edit
+/// deleted a bracket and unbalanced the slice, or introduced one that now
+/// reaches past it.
+pub fn reparse(old_tree: &TagNode, old_source: &str, edit: Range<usize>, replacement: &str) -> TagNode {
+    let delta = replacement.chars().count() as isize - (edit.end - edit.start) as isize;
+    let new_source = splice(old_source, &edit, replacement);
+
+    let target = match find_enclosing(old_tree, &edit) {
+        Some(span) if span != old_tree.span() => span,
+        _ => return full_reparse(&new_source),
+    };
+
+    let new_end = (target.1 as isize + delta) as usize;
+    let slice_start = char_to_byte(&new_source, target.0);
+    let slice_end = char_to_byte(&new_source, new_end);
+    match reparse_slice(&new_source[slice_start..slice_end]) {
+        Some(subtree) => splice_tree(old_tree, &edit, delta, target, &shift_spans(subtree, target.0 as isize)),
+        None => full_reparse(&new_source),
+    }
+}
+
+/// Parse `source` from scratch, discarding any diagnostics - the fallback
+/// path `reparse` uses when it can't safely reuse any of the old tree.
+pub fn full_reparse(source: &str) -> TagNode {
+    let lex_result = tokenize(source);
+    let mut parser = StreamingParser::new(lex_result.tokens);
+    parser.parse().0
+}
+
+/// Re-parse a single bracketed slice in isolation: `Some(tag)` only if the
+/// slice lexes clean, parses as exactly one top-level tag with no
+/// diagnostics, and leaves nothing trailing - anything else (a dangling
+/// bracket, leftover tokens, a parse error) means the edit broke the
+/// slice's balance and the caller should fall back to a full parse.
+fn reparse_slice(slice: &str) -> Option<TagNode> {
+    let lex_result = tokenize(slice);
+    if !lex_result.errors.is_empty() {
+        return None;
+    }
+    let mut parser = StreamingParser::new(lex_result.tokens);
+    let tag = parser.next()?;
+    if parser.next().is_some() || !parser.diagnostics().is_empty() {
+        return None;
+    }
+    match tag {
+        TagNode::Composite { .. } => Some(tag),
+        _ => None,
+    }
+}
+
+/// Walk `node` looking for the smallest `Composite` whose span fully
+/// contains `edit` - only a `Composite` has a bracket pair to re-parse in
+/// isolation, so `Primitive`/`Error`/`FlatList` are never candidates
+/// themselves, though a `FlatList`'s items are still walked for one.
+fn find_enclosing(node: &TagNode, edit: &Range<usize>) -> Option<(usize, usize)> {
+    match node {
+        TagNode::Composite { ltag, rtag, span } => {
+            if span.0 > edit.start || span.1 < edit.end {
+                return None;
+            }
+            find_enclosing(ltag, edit)
+                .or_else(|| find_enclosing(rtag, edit))
+                .or(Some(*span))
+        }
+        TagNode::FlatList { items, span } => {
+            if span.0 > edit.start || span.1 < edit.end {
+                return None;
+            }
+            items.iter().find_map(|item| find_enclosing(item, edit))
+        }
+        TagNode::Primitive(_) | TagNode::Error { .. } => None,
+    }
+}
+
+/// Rebuild `node`, replacing whichever `Composite` has span `target` with
+/// `replacement` and shifting every other node that falls after `edit` by
+/// `delta`. Nodes entirely before the edit are left untouched; nodes on the
+/// path from the root down to `target` contain the edit and so only their
+/// end shifts (their content past the edit moved, their start didn't). That
+/// includes `Primitive`/`Error` nodes, not just `Composite`s: `create_root`
+/// and `create_list_node` stamp their synthetic wrapper keywords with the
+/// same overall span as the tag they wrap, so those spans contain the edit
+/// too even though the node itself has no children to recurse into.
+fn splice_tree(node: &TagNode, edit: &Range<usize>, delta: isize, target: (usize, usize), replacement: &TagNode) -> TagNode {
+    if matches!(node, TagNode::Composite { .. }) && node.span() == target {
+        return replacement.clone();
+    }
+    let span = node.span();
+    if span.0 <= edit.start && span.1 >= edit.end {
+        return match node {
+            TagNode::Composite { ltag, rtag, .. } => TagNode::Composite {
+                ltag: Box::new(splice_tree(ltag, edit, delta, target, replacement)),
+                rtag: Box::new(splice_tree(rtag, edit, delta, target, replacement)),
+                span: (span.0, shift(span.1, delta)),
+            },
+            TagNode::Primitive(prim) => {
+                let mut prim = prim.clone();
+                prim.span = (span.0, shift(span.1, delta));
+                TagNode::Primitive(prim)
+            }
+            TagNode::Error { message, .. } => TagNode::Error {
+                span: (span.0, shift(span.1, delta)),
+                message: message.clone(),
+            },
+            TagNode::FlatList { items, .. } => TagNode::FlatList {
+                items: items
+                    .iter()
+                    .map(|item| splice_tree(item, edit, delta, target, replacement))
+                    .collect(),
+                span: (span.0, shift(span.1, delta)),
+            },
+        };
+    }
+    if span.0 >= edit.end {
+        return shift_spans(node.clone(), delta);
+    }
+    node.clone()
+}
+
+/// Add `delta` to every span in `node`, recursively - used both to move a
+/// freshly re-parsed slice from its own 0-based offsets to its real
+/// position in the document, and to shift whole subtrees that fall after
+/// an edit.
+fn shift_spans(node: TagNode, delta: isize) -> TagNode {
+    match node {
+        TagNode::Composite { ltag, rtag, span } => TagNode::Composite {
+            ltag: Box::new(shift_spans(*ltag, delta)),
+            rtag: Box::new(shift_spans(*rtag, delta)),
+            span: (shift(span.0, delta), shift(span.1, delta)),
+        },
+        TagNode::Primitive(mut prim) => {
+            prim.span = (shift(prim.span.0, delta), shift(prim.span.1, delta));
+            TagNode::Primitive(prim)
+        }
+        TagNode::Error { span, message } => TagNode::Error {
+            span: (shift(span.0, delta), shift(span.1, delta)),
+            message,
+        },
+        TagNode::FlatList { items, span } => TagNode::FlatList {
+            items: items.into_iter().map(|item| shift_spans(item, delta)).collect(),
+            span: (shift(span.0, delta), shift(span.1, delta)),
+        },
+    }
+}
+
+fn shift(offset: usize, delta: isize) -> usize {
+    (offset as isize + delta) as usize
+}
+
+/// Apply a text edit: replace `edit` with `replacement`. A thin public
+/// wrapper around `splice` so a caller driving `reparse` (the REPL's
+/// `:edit` command) can compute the same new source text `reparse` derived
+/// internally, to keep around for the *next* edit.
+pub fn apply_edit(source: &str, edit: Range<usize>, replacement: &str) -> String {
+    splice(source, &edit, replacement)
+}
+
+/// Apply a text edit: replace `edit` with `replacement`.
+///
+/// `edit` is a char-offset range (matching every other span in this
+/// codebase), not a byte range, so it has to be mapped through
+/// `char_to_byte` before slicing `source` - slicing directly on the raw
+/// `usize`s panics the moment a multi-byte char precedes the edit.
+fn splice(source: &str, edit: &Range<usize>, replacement: &str) -> String {
+    let start = char_to_byte(source, edit.start);
+    let end = char_to_byte(source, edit.end);
+    let mut new_source = String::with_capacity(source.len() - (end - start) + replacement.len());
+    new_source.push_str(&source[..start]);
+    new_source.push_str(replacement);
+    new_source.push_str(&source[end..]);
+    new_source
+}
+
+/// Map a char offset into `s` to the matching byte offset, the same
+/// char-indices walk `diagnostic.rs::locate` uses. An offset at or past the
+/// end of `s` maps to `s.len()`.
+fn char_to_byte(s: &str, char_idx: usize) -> usize {
+    s.char_indices().nth(char_idx).map(|(b, _)| b).unwrap_or(s.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply `edit` via `reparse` and via a from-scratch `full_reparse` of
+    /// the same post-edit source, then assert the two trees agree node for
+    /// node (including every span) via their derived `Debug` output -
+    /// `TagNode` has no `PartialEq`, but `Debug` prints every field, so two
+    /// equal strings mean the incremental path reused exactly what a full
+    /// parse would have produced instead of drifting on stale offsets.
+    fn assert_reparse_matches_full_reparse(source: &str, edit: Range<usize>, replacement: &str) {
+        let old_tree = full_reparse(source);
+        let incremental = reparse(&old_tree, source, edit.clone(), replacement);
+        let new_source = apply_edit(source, edit, replacement);
+        let from_scratch = full_reparse(&new_source);
+        assert_eq!(
+            format!("{:?}", incremental),
+            format!("{:?}", from_scratch),
+            "incremental reparse of {:?} diverged from a full reparse of {:?}",
+            source, new_source
+        );
+    }
+
+    #[test]
+    fn reparse_edits_one_sibling_among_several_without_disturbing_the_others() {
+        let source = "[number: 1]  [number: 2]  [number: 3]";
+        let middle_start = source.find("2").unwrap();
+        assert_reparse_matches_full_reparse(source, middle_start..middle_start + 1, "22");
+    }
+
+    #[test]
+    fn reparse_handles_an_insertion_that_grows_the_edited_tag() {
+        let source = "[number: 1]  [number: 999]";
+        let insert_at = source.find("999").unwrap();
+        assert_reparse_matches_full_reparse(source, insert_at..insert_at, "4");
+    }
+
+    #[test]
+    fn reparse_handles_a_deletion_that_shrinks_the_edited_tag() {
+        let source = "[number: 123]  [number: 999]";
+        let digit = source.find("2").unwrap();
+        assert_reparse_matches_full_reparse(source, digit..digit + 1, "");
+    }
+
+    #[test]
+    fn reparse_computes_delta_in_chars_not_bytes_for_a_multibyte_replacement() {
+        // The regression case: "1" -> "\u{e9}" is one char but two UTF-8
+        // bytes, so a delta computed from `replacement.len()` (bytes) would
+        // shift every node after the edit by one char too many.
+        let source = "[number: 1]  [number: 2]";
+        let digit = source.find("1").unwrap();
+        assert_reparse_matches_full_reparse(source, digit..digit + 1, "\u{e9}");
+    }
+
+    #[test]
+    fn reparse_falls_back_to_full_reparse_when_the_edit_unbalances_a_bracket() {
+        let source = "[number: 1]  [number: 2]";
+        let bracket = source.find('[').unwrap();
+        assert_reparse_matches_full_reparse(source, bracket..bracket + 1, "");
+    }
+}
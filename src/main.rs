@@ -5,15 +5,19 @@ mod lexer;
 mod tag;
 mod streaming_parser;
 mod evaluator_v2;
+mod diagnostic;
+mod pipeline;
+mod incremental;
 
 // Standard library imports
 // std::fs - filesystem operations (like C++ <fstream>)
 // std::env - environment and command-line args (like C++ argv)
 use std::fs;
 use std::env;
+use std::io::{self, BufRead, Write};
 // These are specific items from our modules we'll use directly
-use lexer::tokenize;
-use streaming_parser::StreamingParser;
+use lexer::{tokenize, Token};
+use streaming_parser::{ParserConfig, StreamingParser};
 use evaluator_v2::Evaluator;
 
 fn main() {
@@ -21,20 +25,79 @@ fn main() {
     // .collect() consumes the iterator and creates a Vec<String> (like C++ vector<string>)
     // Example: if user runs "packard test.psl", args = ["packard", "test.psl"]
     let args: Vec<String> = env::args().collect();
-    
-    // In Rust, we need at least 2 args: program name + filename
-    // Check the length before accessing by index (prevents panic/crash)
+
+    // With no file argument, drop into an interactive REPL instead of
+    // treating it as a usage error.
     if args.len() < 2 {
-        // eprintln! writes to stderr (like C++ cerr)
-        eprintln!("Usage: packard <script.psl>");
-        std::process::exit(1);  // Exit with error code 1
+        run_repl();
+        return;
     }
 
     // Get the filename (args[0] is program name, args[1] is first real argument)
     // The & means we're borrowing the string, not taking ownership
     // (In Rust, if you move ownership, original variable can't be used anymore)
     let filename = &args[1];
-    
+
+    // Optional `--emit-json <path>` flag: writes the parsed AST and the
+    // final variable store as one JSON document, for editors/test harnesses
+    // that want structured output instead of scraping printed text.
+    let emit_json_path = args
+        .iter()
+        .position(|a| a == "--emit-json")
+        .and_then(|i| args.get(i + 1))
+        .cloned();
+
+    // `--single-diagnostic` trades the file-mode default (report every
+    // lex/parse error in one run) for `pipeline::interpret`'s one-call,
+    // fail-fast-on-first-diagnostic entry point. This is the real caller
+    // `interpret` was missing: a caller who wants "am I clean, and if not,
+    // what's the one thing wrong" rather than a full error dump, e.g. a
+    // pre-commit check that just wants a pass/fail with one pointer.
+    if args.iter().any(|a| a == "--single-diagnostic") {
+        let source = match fs::read_to_string(filename) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading file: {}", e);
+                std::process::exit(1);
+            }
+        };
+        match pipeline::interpret(&source) {
+            Ok((result, store)) => {
+                println!("Result: {}", result);
+                println!("Variable store:");
+                for (key, value) in &store {
+                    println!("  {}: {}", key, value);
+                }
+            }
+            Err(diag) => {
+                eprintln!("{}", diag.render(&source));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Optional shape-constraint flags, built into a `ParserConfig` and fed
+    // to `StreamingParser::with_config` below - the CLI's entry point for
+    // the `ParserConfig` builder (flat_tree/expect_top_level_count/
+    // require_top_level_ltag), which previously had no caller anywhere.
+    let flat_tree = args.iter().any(|a| a == "--flat-tree");
+    let expect_top_level_count: Option<usize> = args
+        .iter()
+        .position(|a| a == "--expect-top-level-count")
+        .and_then(|i| args.get(i + 1))
+        .map(|n| {
+            n.parse().unwrap_or_else(|_| {
+                eprintln!("--expect-top-level-count expects a number, got '{}'", n);
+                std::process::exit(1);
+            })
+        });
+    let require_top_level_ltag = args
+        .iter()
+        .position(|a| a == "--require-top-level-ltag")
+        .and_then(|i| args.get(i + 1))
+        .cloned();
+
     // Read file contents into a String
     // Result<T, E> is Rust's error handling (like C++ exceptions but explicit)
     // match statement unwraps the Result:
@@ -50,84 +113,267 @@ fn main() {
     };
 
     // ============================================================================
-    // STAGE 1: LEXER (tokenize.rs)
-    // Convert the source code string into a Vec<Token>
-    // This breaks down the raw text into meaningful units (keywords, operators, etc)
+    // TOKENIZE -> PARSE -> EVALUATE, driven directly (not via `pipeline::Pipeline`)
     // ============================================================================
-    let tokens = match tokenize(&source) {
-        Ok(tokens) => tokens,
+    // `Pipeline::standard()` is still here for embedders (see pipeline.rs), but
+    // it can only fail with a single `Diagnostic` per stage, so going through
+    // it for the CLI meant a file with several bad tags only ever reported the
+    // first one. Driving `tokenize`/`StreamingParser::parse` directly - the
+    // same way `run_repl()` below already does - lets every lex error and every
+    // parse diagnostic be reported in one run instead of one-at-a-time.
+    let lex_result = tokenize(&source);
+    if !lex_result.errors.is_empty() {
+        for err in &lex_result.errors {
+            eprintln!("{}", err.to_diagnostic().render(&source));
+        }
+        std::process::exit(1);
+    }
+
+    println!("Tokens ({} total):", lex_result.tokens.len());
+    for (i, (token, span)) in lex_result.tokens.iter().enumerate() {
+        println!("  {}: {:?} ({})", i, token, span);
+    }
+
+    let mut config = ParserConfig::new();
+    if flat_tree {
+        config = config.flat_tree();
+    }
+    if let Some(n) = expect_top_level_count {
+        config = config.expect_top_level_count(n);
+    }
+    if let Some(name) = require_top_level_ltag {
+        config = config.require_top_level_ltag(name);
+    }
+
+    let mut parser = StreamingParser::with_config(lex_result.tokens, config);
+    let (root, diagnostics) = parser.parse();
+    if !diagnostics.is_empty() {
+        for diag in &diagnostics {
+            eprintln!("{}", diag.render(&source));
+        }
+        std::process::exit(1);
+    }
+
+    println!("\nParsed root tag:");
+    println!("  {}", format_tag(&root, 2));
+
+    let mut evaluator = match Evaluator::new("eval_trace.log") {
+        Ok(evaluator) => evaluator,
+        Err(e) => {
+            eprintln!("Could not create log file: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let result = match evaluator.execute_root(&root) {
+        Ok(result) => result,
         Err(e) => {
-            eprintln!("Lexer error: {}", e);
+            eprintln!("{}", e.render(&source));
             std::process::exit(1);
         }
     };
 
-    // Print tokens for debugging
-    println!("Tokens ({} total):", tokens.len());
-    // .iter() creates an iterator, .enumerate() gives us (index, item) pairs
-    // In Rust, you iterate explicitly like this (no foreach like C++11)
-    for (i, token) in tokens.iter().enumerate() {
-        // {:?} is the debug format specifier (prints with Debug trait)
-        println!("  {}: {:?}", i, token);
+    println!("\nEvaluation trace written to eval_trace.log");
+    println!("Result: {}", result);
+
+    println!("Variable store:");
+    for (key, value) in &evaluator.store {
+        println!("  {}: {}", key, value);
     }
 
-    // ============================================================================
-    // STAGE 2: PARSER (streaming_parser.rs)
-    // Convert tokens into a tree structure (TagNode)
-    // Each tag is [ltag: rtag] - a binary tree structure
-    // ============================================================================
-    let mut parser = StreamingParser::new(tokens);
-    // mut means mutable - parser will modify its internal position as it reads tokens
-    // (In Rust, variables are immutable by default, unlike C++)
-    
-    let root = match parser.parse() {
-        Ok(root) => root,  // Returns a TagNode tree
+    if let Some(path) = &emit_json_path {
+        if let Err(e) = emit_json(path, &root, &evaluator.store) {
+            eprintln!("Could not write JSON export: {}", e);
+            std::process::exit(1);
+        }
+        println!("\nWrote JSON export to {}", path);
+    }
+}
+
+// ============================================================================
+// REPL MODE
+// ============================================================================
+// Reads lines from stdin, tokenizes and parses them incrementally, and keeps
+// a single Evaluator alive across entries so `evaluator.store` persists
+// between inputs - unlike the file mode above, which runs the four-stage
+// pipeline once and exits.
+fn run_repl() {
+    // One Evaluator for the whole session, so `:store` and cross-entry
+    // variables/attributes behave like they would in a single script.
+    let mut evaluator = match Evaluator::new("eval_trace.log") {
+        Ok(evaluator) => evaluator,
         Err(e) => {
-            eprintln!("Parse error: {}", e);
+            eprintln!("Could not create log file: {}", e);
             std::process::exit(1);
         }
     };
 
-    println!("\nParsed root tag:");
-    // format_tag recursively pretty-prints the tree structure
-    println!("  {}", format_tag(&root, 2));
+    // The most recently evaluated (source, parsed tree) pair, kept around
+    // so `:edit` can reparse just the edited tag via `incremental::reparse`
+    // instead of retyping the whole entry.
+    let mut last_entry: Option<(String, tag::TagNode)> = None;
 
-    // ============================================================================
-    // STAGE 3 & 4: VALIDATOR + EVALUATOR (evaluator_v2.rs)
-    // Validate the tree, then execute it
-    // ============================================================================
-    match Evaluator::new("eval_trace.log") {
-        Ok(mut evaluator) => {
-            // Evaluator needs to be mut because execute_root() modifies its internal state
-            // (frame stack, variable store, log file, eval counter, etc)
-            
-            match evaluator.execute_root(&root) {
-                Ok(result) => {
-                    // Execution succeeded!
-                    println!("\nEvaluation trace written to eval_trace.log");
-                    println!("Result: {}", result);
-                    
-                    // Print the global variable store (HashMap of variable names → values)
-                    println!("Variable store:");
-                    // We use & to borrow evaluator.store, not take ownership
-                    // This lets us print it without consuming it
+    let stdin = io::stdin();
+    let mut buffer = String::new();
+
+    loop {
+        // An empty buffer means we're starting a fresh entry; a non-empty
+        // one means we're in the middle of a multiline tag and waiting on
+        // more input, so show a continuation prompt instead.
+        print!("{}", if buffer.is_empty() { "psl> " } else { "... " });
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;  // EOF (Ctrl-D): stop the REPL
+        }
+
+        if buffer.is_empty() {
+            match line.trim() {
+                ":quit" => break,
+                ":store" => {
                     for (key, value) in &evaluator.store {
                         println!("  {}: {}", key, value);
                     }
+                    continue;
                 }
-                Err(e) => {
-                    eprintln!("Evaluation error: {}", e);
-                    std::process::exit(1);
+                rest if rest.starts_with(":edit ") => {
+                    run_edit_command(&rest[":edit ".len()..], &mut last_entry, &mut evaluator);
+                    continue;
                 }
+                _ => {}
             }
         }
-        Err(e) => {
-            eprintln!("Could not create log file: {}", e);
-            std::process::exit(1);
+
+        buffer.push_str(&line);
+
+        if is_incomplete(&buffer) {
+            continue;  // Keep accumulating lines until the tag is balanced
+        }
+
+        let source = std::mem::take(&mut buffer);
+        let lex_result = tokenize(&source);
+        if !lex_result.errors.is_empty() {
+            for err in &lex_result.errors {
+                eprintln!("{}", err.to_diagnostic().render(&source));
+            }
+            continue;
+        }
+
+        let mut parser = StreamingParser::new(lex_result.tokens);
+        let (root, diagnostics) = parser.parse();
+        for diag in &diagnostics {
+            eprintln!("{}", diag.render(&source));
+        }
+        if !diagnostics.is_empty() {
+            continue;
+        }
+
+        match evaluator.execute_root(&root) {
+            Ok(result) => {
+                println!("{}", result);
+                last_entry = Some((source, root));
+            }
+            Err(e) => eprintln!("{}", e.render(&source)),
+        }
+    }
+}
+
+// Parse and run a `:edit <start> <end> <replacement>` REPL command: replace
+// char offsets `[start, end)` of the last entry's source with `replacement`,
+// incrementally reparse via `incremental::reparse` instead of rerunning the
+// whole tokenize/parse pipeline, and re-evaluate the result. `replacement`
+// runs to the end of the line, so it may itself contain spaces or colons.
+fn run_edit_command(
+    args: &str,
+    last_entry: &mut Option<(String, tag::TagNode)>,
+    evaluator: &mut Evaluator,
+) {
+    let (source, root) = match last_entry {
+        Some(entry) => entry,
+        None => {
+            eprintln!("No previous entry to edit - enter a tag first");
+            return;
         }
+    };
+
+    let mut parts = args.splitn(3, ' ');
+    let (start, end, replacement) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(start), Some(end), replacement) => (start, end, replacement.unwrap_or("")),
+        _ => {
+            eprintln!("Usage: :edit <start> <end> <replacement>");
+            return;
+        }
+    };
+    let (start, end) = match (start.parse::<usize>(), end.parse::<usize>()) {
+        (Ok(start), Ok(end)) if start <= end && end <= source.chars().count() => (start, end),
+        _ => {
+            eprintln!("Invalid edit range (expected 0 <= start <= end <= {})", source.chars().count());
+            return;
+        }
+    };
+
+    let new_root = incremental::reparse(root, source, start..end, replacement);
+    let new_source = incremental::apply_edit(source, start..end, replacement);
+
+    match evaluator.execute_root(&new_root) {
+        Ok(result) => {
+            println!("{}", result);
+            *source = new_source;
+            *root = new_root;
+        }
+        Err(e) => eprintln!("{}", e.render(&new_source)),
     }
 }
 
+// Before handing a buffer to the parser, scan its token stream for an
+// unbalanced `[`/`]` nesting, or a trailing `:` that's still expecting its
+// rtag - either means the tag isn't finished yet and the REPL should print
+// a continuation prompt and keep appending lines instead of parsing.
+fn is_incomplete(source: &str) -> bool {
+    let lex_result = tokenize(source);
+    let mut depth: i32 = 0;
+    let mut last_meaningful: Option<&Token> = None;
+
+    for (token, _) in &lex_result.tokens {
+        match token {
+            Token::OpenBracket => depth += 1,
+            Token::CloseBracket => depth -= 1,
+            Token::Eof => continue,
+            _ => {}
+        }
+        last_meaningful = Some(token);
+    }
+
+    depth > 0 || matches!(last_meaningful, Some(Token::Colon))
+}
+
+// ============================================================================
+// JSON EXPORT (--emit-json)
+// ============================================================================
+// A structured, machine-readable counterpart to format_tag's human-facing
+// pretty print - the parsed AST and the final variable store as tagged-union
+// JSON, so editors and test harnesses can consume Packard output without
+// scraping text.
+
+/// One JSON document combining everything downstream tooling needs: the
+/// parsed tree and the variable bindings evaluation left behind.
+#[derive(serde::Serialize)]
+struct JsonExport<'a> {
+    ast: &'a tag::TagNode,
+    store: &'a std::collections::HashMap<String, tag::Value>,
+}
+
+fn emit_json(
+    path: &str,
+    root: &tag::TagNode,
+    store: &std::collections::HashMap<String, tag::Value>,
+) -> std::io::Result<()> {
+    let export = JsonExport { ast: root, store };
+    let json = serde_json::to_string_pretty(&export)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+    fs::write(path, json)
+}
+
 // Helper function to pretty-print a TagNode tree with indentation
 // This is a recursive function that walks the entire tree
 // Parameters:
@@ -148,7 +394,7 @@ fn format_tag(tag: &tag::TagNode, indent: usize) -> String {
         // Case 2: Composite tag with ltag and rtag
         // The { ltag, rtag } syntax destructures the struct fields
         // (like unpacking a tuple in C++17)
-        tag::TagNode::Composite { ltag, rtag } => {
+        tag::TagNode::Composite { ltag, rtag, .. } => {
             // format! is like sprintf in C++ - builds a string from template + args
             // \n is a newline
             format!(
@@ -162,5 +408,19 @@ fn format_tag(tag: &tag::TagNode, indent: usize) -> String {
                 " ".repeat(indent - 2)
             )
         }
+
+        // Case 3: a parser recovery placeholder - the source had a broken
+        // tag here, but parsing kept going past it
+        tag::TagNode::Error { message, .. } => format!("<error: {}>", message),
+
+        // Case 4: a flat top-level list (ParserConfig::flat_tree), printed
+        // as an indented, bracketed sequence instead of a ltag/rtag pair
+        tag::TagNode::FlatList { items, .. } => {
+            let rendered: Vec<String> = items
+                .iter()
+                .map(|item| format!("{}{}", " ".repeat(indent), format_tag(item, indent + 2)))
+                .collect();
+            format!("[\n{}\n{}]", rendered.join(",\n"), " ".repeat(indent - 2))
+        }
     }
 }
\ No newline at end of file
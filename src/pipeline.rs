@@ -0,0 +1,236 @@
+// ============================================================================
+// PIPELINE - library entry point chaining the interpreter stages
+// ============================================================================
+// main() used to inline tokenize -> parse -> validate -> evaluate directly,
+// so nothing about running a Packard program could be reused without
+// copy-pasting that glue. This factors the same steps into a `Stage` trait
+// and a `Pipeline` that chains them, so the crate works as a library
+// (embedding, alternate backends, post-evaluation plugins) and not just as
+// the `packard` CLI binary.
+//
+// The CLI's default file-mode driver goes around this module: `Stage::run`
+// can only fail with one `Diagnostic`, which would make the default file
+// mode report just the first lex/parse error in a file instead of every
+// one, so main() drives `tokenize`/`StreamingParser::parse` directly there
+// (matching what `run_repl()` already did). `interpret` is still a real,
+// called entry point though: main()'s `--single-diagnostic` flag goes
+// through `Pipeline::standard().run` for callers who want a single
+// pass/fail result with one pointer instead of a full error dump.
+use crate::diagnostic::Diagnostic;
+use crate::evaluator_v2::Evaluator;
+use crate::lexer::{tokenize, Span, Token};
+use crate::streaming_parser::StreamingParser;
+use crate::tag::{TagNode, Value};
+use std::collections::HashMap;
+
+/// The variable bindings left behind after evaluation.
+pub type Store = HashMap<String, Value>;
+
+/// The artifact flowing into a stage. A stage only inspects the variant it
+/// expects; the enum lets `Pipeline` chain stages uniformly instead of
+/// needing one chain type per concrete input/output pair.
+pub enum StageInput {
+    Source(String),
+    Tokens(Vec<(Token, Span)>),
+    Ast(TagNode),
+    Evaluated(Value, Store),
+}
+
+pub enum StageOutput {
+    Tokens(Vec<(Token, Span)>),
+    Ast(TagNode),
+    Evaluated(Value, Store),
+}
+
+/// One step of the pipeline. Implement this to plug a custom stage into a
+/// `Pipeline` - e.g. a post-evaluation printer or optimizer registered via
+/// `Pipeline::with_stage`.
+pub trait Stage {
+    fn run(&self, input: StageInput) -> Result<StageOutput, Diagnostic>;
+}
+
+/// Lexing: source text to a spanned token stream. `tokenize` collects every
+/// lexing error it finds, but a `Stage` can only fail with one `Diagnostic`,
+/// so only the first is surfaced here - callers that want every lexer error
+/// at once should call `lexer::tokenize` directly instead of going through
+/// the pipeline.
+struct TokenizeStage;
+
+impl Stage for TokenizeStage {
+    fn run(&self, input: StageInput) -> Result<StageOutput, Diagnostic> {
+        let source = match input {
+            StageInput::Source(s) => s,
+            _ => return Err(Diagnostic::new("TokenizeStage expects Source input", 0, 0)),
+        };
+        let lex_result = tokenize(&source);
+        if let Some(first) = lex_result.errors.first() {
+            return Err(first.to_diagnostic());
+        }
+        Ok(StageOutput::Tokens(lex_result.tokens))
+    }
+}
+
+/// Parsing: spanned tokens to a `TagNode` tree. The parser itself recovers
+/// from broken tags and keeps going, but a `Stage` can only fail with one
+/// `Diagnostic` - as with `TokenizeStage`, only the first is surfaced here;
+/// callers that want every parse error at once should drive
+/// `StreamingParser::parse` directly instead of going through the pipeline.
+struct ParseStage;
+
+impl Stage for ParseStage {
+    fn run(&self, input: StageInput) -> Result<StageOutput, Diagnostic> {
+        let tokens = match input {
+            StageInput::Tokens(t) => t,
+            _ => return Err(Diagnostic::new("ParseStage expects Tokens input", 0, 0)),
+        };
+        let mut parser = StreamingParser::new(tokens);
+        let (root, diagnostics) = parser.parse();
+        if let Some(first) = diagnostics.into_iter().next() {
+            return Err(first);
+        }
+        Ok(StageOutput::Ast(root))
+    }
+}
+
+/// Validation + evaluation in one stage: `Evaluator::execute_root` already
+/// validates before it evaluates, so splitting them into two `Stage`s would
+/// just mean re-running validation or threading the `Evaluator` through an
+/// extra step for no benefit.
+struct EvaluateStage;
+
+impl Stage for EvaluateStage {
+    fn run(&self, input: StageInput) -> Result<StageOutput, Diagnostic> {
+        let root = match input {
+            StageInput::Ast(root) => root,
+            _ => return Err(Diagnostic::new("EvaluateStage expects Ast input", 0, 0)),
+        };
+        let mut evaluator = Evaluator::new("eval_trace.log")
+            .map_err(|e| Diagnostic::new(format!("Could not create log file: {}", e), 0, 0))?;
+        let result = evaluator.execute_root(&root)?;
+        Ok(StageOutput::Evaluated(result, evaluator.store))
+    }
+}
+
+/// Chains `Stage`s end to end, optionally keeping the intermediate token
+/// vec and parsed `TagNode` around (via `tokens()`/`ast()`) for callers
+/// that want to inspect the pipeline instead of just its final result.
+pub struct Pipeline {
+    stages: Vec<Box<dyn Stage>>,
+    tokens: Option<Vec<(Token, Span)>>,
+    ast: Option<TagNode>,
+}
+
+impl Pipeline {
+    /// The standard tokenize -> parse -> evaluate pipeline (evaluate
+    /// validates first). Stages registered afterward via `with_stage` run
+    /// on the `(Value, Store)` result, e.g. a custom printer or optimizer.
+    pub fn standard() -> Self {
+        Pipeline {
+            stages: vec![Box::new(TokenizeStage), Box::new(ParseStage), Box::new(EvaluateStage)],
+            tokens: None,
+            ast: None,
+        }
+    }
+
+    /// Register an extra stage to run after whatever the pipeline already
+    /// ends with - for `standard()`, that's the evaluated `(Value, Store)`.
+    pub fn with_stage(mut self, stage: impl Stage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// The token stream produced the last time `run` was called, if it got
+    /// that far.
+    pub fn tokens(&self) -> Option<&[(Token, Span)]> {
+        self.tokens.as_deref()
+    }
+
+    /// The parsed tree produced the last time `run` was called, if it got
+    /// that far.
+    pub fn ast(&self) -> Option<&TagNode> {
+        self.ast.as_ref()
+    }
+
+    /// Run every registered stage in order over `source`, capturing the
+    /// token vec and AST along the way.
+    pub fn run(&mut self, source: &str) -> Result<(Value, Store), Diagnostic> {
+        let mut current = StageInput::Source(source.to_string());
+
+        for i in 0..self.stages.len() {
+            let output = self.stages[i].run(current)?;
+            current = match output {
+                StageOutput::Tokens(tokens) => {
+                    self.tokens = Some(tokens.clone());
+                    StageInput::Tokens(tokens)
+                }
+                StageOutput::Ast(root) => {
+                    self.ast = Some(root.clone());
+                    StageInput::Ast(root)
+                }
+                StageOutput::Evaluated(value, store) => StageInput::Evaluated(value, store),
+            };
+        }
+
+        match current {
+            StageInput::Evaluated(value, store) => Ok((value, store)),
+            _ => Err(Diagnostic::new("Pipeline did not end in an evaluated result", 0, 0)),
+        }
+    }
+}
+
+/// Run the full tokenize -> parse -> validate -> evaluate pipeline over
+/// `source` in one call - the library entry point for embedding the
+/// interpreter, and what main()'s `--single-diagnostic` flag calls. The
+/// CLI's default file mode doesn't go through it: it drives `tokenize` and
+/// `StreamingParser::parse` itself so it can print the token dump, the
+/// parsed tree, and every diagnostic rather than just the first.
+pub fn interpret(source: &str) -> Result<(Value, Store), Diagnostic> {
+    Pipeline::standard().run(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpret_runs_the_standard_pipeline_end_to_end() {
+        let (value, _store) = interpret("[number: 42]").expect("valid program should evaluate");
+        assert_eq!(value, Value::Number(42.0));
+    }
+
+    #[test]
+    fn interpret_surfaces_the_first_parse_diagnostic() {
+        let err = interpret("[number: ").unwrap_err();
+        assert!(!err.message.is_empty());
+    }
+
+    #[test]
+    fn pipeline_captures_intermediate_tokens_and_ast() {
+        let mut pipeline = Pipeline::standard();
+        pipeline.run("[number: 42]").expect("valid program should evaluate");
+        assert!(pipeline.tokens().is_some());
+        assert!(pipeline.ast().is_some());
+    }
+
+    #[test]
+    fn with_stage_runs_after_the_standard_pipeline() {
+        struct DoubleNumber;
+        impl Stage for DoubleNumber {
+            fn run(&self, input: StageInput) -> Result<StageOutput, Diagnostic> {
+                match input {
+                    StageInput::Evaluated(Value::Number(n), store) => {
+                        Ok(StageOutput::Evaluated(Value::Number(n * 2.0), store))
+                    }
+                    StageInput::Evaluated(value, store) => Ok(StageOutput::Evaluated(value, store)),
+                    _ => Err(Diagnostic::new("DoubleNumber expects Evaluated input", 0, 0)),
+                }
+            }
+        }
+
+        let (value, _store) = Pipeline::standard()
+            .with_stage(DoubleNumber)
+            .run("[number: 21]")
+            .expect("valid program should evaluate");
+        assert_eq!(value, Value::Number(42.0));
+    }
+}
@@ -1,5 +1,82 @@
-use crate::lexer::Token;  // Token types from the lexer
-use crate::tag::{TagNode, Primitive};  // Types we're building
+use crate::diagnostic::Diagnostic;
+use crate::lexer::{Span, Token};  // Token and source-location types from the lexer
+use crate::tag::{Primitive, PrimitiveKind, TagNode};  // Types we're building
+
+// ============================================================================
+// PARSER CONFIG - builder for shape constraints embedders can opt into
+// ============================================================================
+// By default the parser accepts any sequence of top-level tags and always
+// builds the right-nested cons chain `create_list_node` has always built.
+// Embedders that want to constrain input shape (a fixed-arity file format,
+// every top-level tag starting with the same keyword) used to have to
+// hand-write a post-parse walk over the tree to check it; `ParserConfig`
+// lets them ask for that at parse time instead, surfaced as an ordinary
+// parse diagnostic rather than a second pass.
+
+/// Builder-style configuration for `StreamingParser::with_config`, in the
+/// vein of syn-rsx's `ParserConfig`: each method consumes and returns
+/// `self` so calls chain, e.g.
+/// `ParserConfig::new().flat_tree().expect_top_level_count(3)`.
+#[derive(Debug, Clone, Default)]
+pub struct ParserConfig {
+    flat_tree: bool,
+    expect_top_level_count: Option<usize>,
+    require_top_level_ltag: Option<String>,
+}
+
+impl ParserConfig {
+    pub fn new() -> Self {
+        ParserConfig::default()
+    }
+
+    /// Build the top-level tags as a real `TagNode::FlatList` instead of
+    /// the right-nested `[tag1: [tag2: [tag3: item]]]` cons chain - easier
+    /// for consumers that just want to iterate siblings. The elements of a
+    /// `[pattern*: [...]]` repetition are built as a `FlatList` too.
+    pub fn flat_tree(mut self) -> Self {
+        self.flat_tree = true;
+        self
+    }
+
+    /// Error unless the program contains exactly `n` top-level tags.
+    pub fn expect_top_level_count(mut self, n: usize) -> Self {
+        self.expect_top_level_count = Some(n);
+        self
+    }
+
+    /// Error unless every top-level tag's `ltag` is the identifier/keyword
+    /// `name` - e.g. `require_top_level_ltag("def")` rejects a file whose
+    /// top tags aren't all `[def: ...]`.
+    pub fn require_top_level_ltag(mut self, name: impl Into<String>) -> Self {
+        self.require_top_level_ltag = Some(name.into());
+        self
+    }
+}
+
+// ============================================================================
+// REPETITION MATCHING - `[pattern*: [elem, elem, ...]]`
+// ============================================================================
+// The `elem (, elem)*` sub-pattern inside a `*` repetition is matched with a
+// single cursor (before/after an element) plus the elements bound so far -
+// see `StreamingParser::match_repetition`. This used to be modeled as a set
+// of NFA "threads" that forked at each `,`, in the spirit of rustc's
+// `macro_parser`, with ambiguous matches resolving to whichever thread
+// completed first. But `elem (, elem)*` has no alternation, so there was
+// never more than one live thread and the fork never ran - a plain cursor
+// says the same thing without claiming a forking capability this grammar
+// never exercises. If repetition patterns ever gain alternation, that's
+// when an actual multi-thread matcher becomes worth the complexity.
+
+/// Where the repetition cursor sits relative to the last element matched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RepetitionCursor {
+    /// Before the first element, or just past a `,` - expects another
+    /// element next, but closing the bracket here is also valid (the
+    /// empty repetition).
+    BeforeElement,
+    /// Just matched an element - expects `,` to continue, or `]` to stop.
+    AfterElement,
+}
 
 // ============================================================================
 // PARSER STATE MACHINE
@@ -30,16 +107,19 @@ struct TagInProgress {
     state: TagParseState,  // Are we parsing ltag or rtag?
     ltag: Option<TagNode>,  // Left tag (Some once we've parsed it)
     rtag: Option<TagNode>,  // Right tag (Some once we've parsed it)
+    start: usize,           // Char offset of this tag's opening `[`
 }
 
 impl TagInProgress {
-    /// Create a new empty tag (ltag and rtag are None)
+    /// Create a new empty tag (ltag and rtag are None), starting at the char
+    /// offset of the `[` that opened it.
     /// Starts in ParsingLTag state - we expect ltag first
-    fn new() -> Self {
+    fn new(start: usize) -> Self {
         TagInProgress {
             state: TagParseState::ParsingLTag,
             ltag: None,
             rtag: None,
+            start,
         }
     }
 
@@ -49,11 +129,12 @@ impl TagInProgress {
         self.ltag.is_some() && self.rtag.is_some()
     }
 
-    /// Convert the completed parts into a composite TagNode
+    /// Convert the completed parts into a composite TagNode, spanning from
+    /// this tag's opening `[` to the `end` offset of its closing `]`.
     /// Uses .clone() because we need to move the values out of Option
     /// .ok_or() converts None into an error: None.ok_or("msg") = Err("msg")
     /// The ? operator returns the error immediately if any step fails
-    fn to_composite(&self) -> Result<TagNode, String> {
+    fn to_composite(&self, end: usize) -> Result<TagNode, String> {
         // Extract values from Options, or return error if None
         let ltag = self.ltag.clone().ok_or("Missing ltag")?;
         let rtag = self.rtag.clone().ok_or("Missing rtag")?;
@@ -61,6 +142,7 @@ impl TagInProgress {
         Ok(TagNode::Composite {
             ltag: Box::new(ltag),
             rtag: Box::new(rtag),
+            span: (self.start, end),
         })
     }
 }
@@ -72,144 +154,314 @@ impl TagInProgress {
 // Uses a stack to handle nested tags
 // Each item on the stack represents a tag being constructed at that nesting level
 
-pub struct StreamingParser {
-    tokens: Vec<Token>,              // Input token stream from lexer
-    position: usize,                 // Current position in the token stream
+/// Pulls tokens from `I` one at a time instead of requiring the whole
+/// stream up front, so `StreamingParser` itself can be driven lazily (see
+/// `parser_from`/the `Iterator` impl below) - a lexer can be piped straight
+/// into the parser without either side materializing the full token list.
+pub struct StreamingParser<I: Iterator<Item = (Token, Span)>> {
+    tokens: std::iter::Peekable<I>,  // Input token stream, pulled on demand
     tag_stack: Vec<TagInProgress>,   // Stack of tags being constructed
                                      // When we see [, we push; when we see ], we pop
+    last_span: Span,                 // Span of the last token pulled, for EOF fallback
+    diagnostics: Vec<Diagnostic>,    // Errors recorded so far in recovery mode
+    config: ParserConfig,            // Shape constraints checked by `parse`
 }
 
-impl StreamingParser {
-    /// Create a new parser with the given token stream
-    /// Takes ownership of the tokens (no & means ownership transfer)
-    pub fn new(tokens: Vec<Token>) -> Self {
+impl<I: Iterator<Item = (Token, Span)>> StreamingParser<I> {
+    /// Create a parser that pulls spanned tokens lazily from any iterator -
+    /// e.g. a lexer's own iterator, so huge or never-ending input (a REPL,
+    /// a socket) never has to be collected into a `Vec` first.
+    pub fn parser_from(tokens: I) -> Self {
+        Self::parser_from_with_config(tokens, ParserConfig::default())
+    }
+
+    /// Like `parser_from`, but with a `ParserConfig` controlling the shape
+    /// of the top-level tags `parse` builds and accepts.
+    pub fn parser_from_with_config(tokens: I, config: ParserConfig) -> Self {
         StreamingParser {
-            tokens,
-            position: 0,          // Start at beginning
-            tag_stack: Vec::new(),  // Stack starts empty
+            tokens: tokens.peekable(),
+            tag_stack: Vec::new(),
+            last_span: Span { line: 1, col: 1, offset: 0, end: 0 },
+            diagnostics: Vec::new(),
+            config,
         }
     }
 
     /// Get the current token without advancing
-    /// .get() returns Option<&Token> (None if out of bounds)
+    /// .peek() looks at the next item the underlying iterator would yield
+    /// without consuming it - `Option<&(Token, Span)>` (None once exhausted)
     /// .unwrap_or() provides default value if None
-    fn current(&self) -> &Token {
-        self.tokens.get(self.position).unwrap_or(&Token::Eof)
+    fn current(&mut self) -> &Token {
+        self.tokens.peek().map(|(t, _)| t).unwrap_or(&Token::Eof)
     }
 
-    /// Move to the next token
-    /// Checks bounds to avoid panic
+    /// Span of the current token, used to locate error messages. Falls back
+    /// to the span of the last token pulled (typically EOF) once the
+    /// underlying iterator is exhausted.
+    fn current_span(&mut self) -> Span {
+        self.tokens.peek().map(|(_, s)| *s).unwrap_or(self.last_span)
+    }
+
+    /// Move to the next token, pulling it from the underlying iterator
     fn advance(&mut self) {
-        if self.position < self.tokens.len() {
-            self.position += 1;
+        if let Some((_, span)) = self.tokens.next() {
+            self.last_span = span;
+        }
+    }
+
+    /// Record a failure without touching the token stream: push a
+    /// `Diagnostic` onto `self.diagnostics` and hand back a `TagNode::Error`
+    /// carrying the same span and message, so the caller can drop it in
+    /// wherever a real tag was expected and keep building the rest of the
+    /// tree around it.
+    fn record_error(&mut self, span: (usize, usize), message: impl Into<String>) -> TagNode {
+        let message = message.into();
+        self.diagnostics.push(Diagnostic::new(message.clone(), span.0, span.1));
+        TagNode::Error { span, message }
+    }
+
+    /// Like `record_error`, but for failures that leave the token stream in
+    /// an unknown state (an unexpected token, a missing `[`) rather than one
+    /// where a matching `]` was already consumed - resynchronizes afterward
+    /// so the next call to `parse_one_tag`/`next` starts from a sane spot
+    /// instead of tripping over the same broken input forever.
+    fn recover(&mut self, span: (usize, usize), message: impl Into<String>) -> TagNode {
+        let node = self.record_error(span, message);
+        self.synchronize();
+        node
+    }
+
+    /// Diagnostics collected so far, without draining them - for a caller
+    /// driving the parser directly as an iterator (rather than through
+    /// `parse`) that wants to check whether anything went wrong.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Skip tokens until we're at a plausible place to resume parsing: the
+    /// end of input, just past a `]` (the broken tag's probable close), or
+    /// right at a `[` (the start of the next tag). Also clears the tag
+    /// stack, since whatever nesting we'd built up belongs to the tag we
+    /// just gave up on.
+    fn synchronize(&mut self) {
+        self.tag_stack.clear();
+        loop {
+            match self.current() {
+                Token::Eof => break,
+                Token::CloseBracket => {
+                    self.advance();
+                    break;
+                }
+                Token::OpenBracket => break,
+                _ => self.advance(),
+            }
         }
     }
 
-    /// Main entry point: parse all tokens into a TagNode tree
-    /// Returns Result - either a single root TagNode or an error
-    pub fn parse(&mut self) -> Result<TagNode, String> {
+    /// Convenience wrapper around the `Iterator` impl below: pull every
+    /// top-level tag the underlying token stream has to offer and fold
+    /// them into the implicit root list, same as the one-shot parser this
+    /// replaced. Callers that want to stream tags one at a time (a REPL, a
+    /// socket) should drive `StreamingParser` as an iterator instead.
+    ///
+    /// Unlike the one-shot parser this replaced, a broken tag doesn't stop
+    /// the whole parse: it's recorded as a `TagNode::Error` placeholder
+    /// (see `recover`) and parsing carries on, so the second returned value
+    /// carries every diagnostic collected along the way - empty if the
+    /// source parsed clean.
+    pub fn parse(&mut self) -> (TagNode, Vec<Diagnostic>) {
         let mut tags = Vec::new();
 
-        // Parse tags until we hit EOF
-        // Note: we don't use a for loop because parse_one_tag mutates self.position
-        while self.current() != &Token::Eof {
-            let tag = self.parse_one_tag()?;  // ? propagates any error
+        while let Some(tag) = self.next() {
             tags.push(tag);
         }
 
+        self.check_shape(&tags);
+
         // Wrap all top-level tags in an implicit root list
         // This normalizes input: `[a] [b]` becomes `[root: [list: [a, [b, ...]]]]`
-        Self::create_root(tags)
+        (self.create_root(tags), std::mem::take(&mut self.diagnostics))
+    }
+
+    /// Check the collected top-level `tags` against `self.config`'s shape
+    /// constraints, pushing a `Diagnostic` for each violation onto
+    /// `self.diagnostics` - surfaced through `parse`'s returned diagnostics
+    /// list exactly like any other parse error.
+    fn check_shape(&mut self, tags: &[TagNode]) {
+        if let Some(expected) = self.config.expect_top_level_count {
+            if tags.len() != expected {
+                let span = Self::overall_span(tags);
+                self.diagnostics.push(Diagnostic::new(
+                    format!("Expected exactly {} top-level tag(s), found {}", expected, tags.len()),
+                    span.0,
+                    span.1,
+                ));
+            }
+        }
+
+        if let Some(required) = self.config.require_top_level_ltag.clone() {
+            for tag in tags {
+                let found = Self::top_level_ltag_name(tag);
+                if found.as_deref() != Some(required.as_str()) {
+                    let span = tag.span();
+                    self.diagnostics.push(Diagnostic::new(
+                        format!(
+                            "Expected top-level ltag '{}', found {}",
+                            required,
+                            found.as_deref().unwrap_or("<non-tag>")
+                        ),
+                        span.0,
+                        span.1,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// The span covering all of `tags`, or a zero-width span at the origin
+    /// if there are none - used to anchor a `Diagnostic` that's about the
+    /// program's overall shape rather than any one tag.
+    fn overall_span(tags: &[TagNode]) -> (usize, usize) {
+        match (tags.first(), tags.last()) {
+            (Some(first), Some(last)) => (first.span().0, last.span().1),
+            _ => (0, 0),
+        }
+    }
+
+    /// A top-level tag's `ltag`, as text - walking the ltag chain to the
+    /// innermost primitive if it's itself composite, same as
+    /// `Evaluator::extract_operation_name`. `None` if `tag` isn't a
+    /// `Composite`, or its ltag chain doesn't bottom out in text.
+    fn top_level_ltag_name(tag: &TagNode) -> Option<String> {
+        match tag {
+            TagNode::Composite { ltag, .. } => match ltag.as_ref() {
+                TagNode::Primitive(prim) => prim.as_text(),
+                inner @ TagNode::Composite { .. } => Self::top_level_ltag_name(inner),
+                _ => None,
+            },
+            _ => None,
+        }
     }
 
     /// Create the implicit root wrapper around all top-level tags
     /// Every program becomes: [root: [list: ... tags ...]]
     /// This normalizes the structure so evaluator always sees a single root
-    fn create_root(tags: Vec<TagNode>) -> Result<TagNode, String> {
-        // Note: all three branches are identical (could be simplified)
-        // They're kept separate for clarity about what's happening
-        
+    fn create_root(&self, tags: Vec<TagNode>) -> TagNode {
+        // Note: all three branches are identical apart from the span they
+        // compute (could be simplified) - kept separate for clarity about
+        // what's happening
         if tags.is_empty() {
-            // Empty program: [root: [list: empty_list]]
-            Ok(TagNode::Composite {
-                ltag: Box::new(TagNode::Primitive(Primitive::Keyword("root".to_string()))),
-                rtag: Box::new(Self::create_list_node(vec![])),
-            })
+            // Empty program: [root: [list: empty_list]] - nothing in the
+            // source to point a span at, so it's a zero-width (0, 0).
+            let span = (0, 0);
+            TagNode::Composite {
+                ltag: Box::new(TagNode::Primitive(Primitive::new(PrimitiveKind::Keyword("root".to_string()), span))),
+                rtag: Box::new(self.create_list_node(vec![], span)),
+                span,
+            }
         } else if tags.len() == 1 {
             // Single tag: [root: [list: tag]]
-            Ok(TagNode::Composite {
-                ltag: Box::new(TagNode::Primitive(Primitive::Keyword("root".to_string()))),
-                rtag: Box::new(Self::create_list_node(tags)),
-            })
+            let span = tags[0].span();
+            TagNode::Composite {
+                ltag: Box::new(TagNode::Primitive(Primitive::new(PrimitiveKind::Keyword("root".to_string()), span))),
+                rtag: Box::new(self.create_list_node(tags, span)),
+                span,
+            }
         } else {
             // Multiple tags: [root: [list: [tag1, [tag2, ...]]]]
-            Ok(TagNode::Composite {
-                ltag: Box::new(TagNode::Primitive(Primitive::Keyword("root".to_string()))),
-                rtag: Box::new(Self::create_list_node(tags)),
-            })
+            let span = (tags.first().unwrap().span().0, tags.last().unwrap().span().1);
+            TagNode::Composite {
+                ltag: Box::new(TagNode::Primitive(Primitive::new(PrimitiveKind::Keyword("root".to_string()), span))),
+                rtag: Box::new(self.create_list_node(tags, span)),
+                span,
+            }
         }
     }
 
-    /// Create a list node structure from multiple tags
-    /// Multiple tags get nested as: [tag1: [tag2: [tag3: item]]]
-    /// This is how lists are represented in the tag language
-    fn create_list_node(mut tags: Vec<TagNode>) -> TagNode {
-        if tags.is_empty() {
-            // Empty list: [list: item]
-            TagNode::Composite {
-                ltag: Box::new(TagNode::Primitive(Primitive::Keyword("list".to_string()))),
-                rtag: Box::new(TagNode::Primitive(Primitive::Keyword("item".to_string()))),
-            }
-        } else if tags.len() == 1 {
-            // Single item: [list: tag]
-            TagNode::Composite {
-                ltag: Box::new(TagNode::Primitive(Primitive::Keyword("list".to_string()))),
-                rtag: Box::new(tags.pop().unwrap()),  // .pop() takes last element and returns Option
-            }
+    /// Create a list node structure from multiple tags. With
+    /// `ParserConfig::flat_tree`, the chain is a real `TagNode::FlatList`;
+    /// otherwise tags are nested as: [tag1: [tag2: [tag3: item]]] - every
+    /// chain always ends in the `item` keyword, even for zero or one
+    /// elements, so the evaluator can walk the chain and know unambiguously
+    /// where the last real element is instead of guessing whether a given
+    /// node is "one more link" or "the final element" (needed so
+    /// `evaluate_tag` can fold a list tag into a `Value::List` of its
+    /// elements).
+    /// `span` is the overall range the synthetic wrapper nodes are stamped
+    /// with - they don't correspond to any single bracket pair the user
+    /// wrote.
+    fn create_list_node(&self, tags: Vec<TagNode>, span: (usize, usize)) -> TagNode {
+        // Wrap the chain in [list: ...]
+        TagNode::Composite {
+            ltag: Box::new(TagNode::Primitive(Primitive::new(PrimitiveKind::Keyword("list".to_string()), span))),
+            rtag: Box::new(self.build_chain(tags, span)),
+            span,
+        }
+    }
+
+    /// Build the rtag half of a `[wrapper: ...]` composite out of zero or
+    /// more `tags` - shared by `create_list_node` (wrapper is always the
+    /// `list` keyword) and `parse_repetition_tag`'s `*` expansion (wrapper
+    /// is whatever pattern name preceded the `*`). With
+    /// `ParserConfig::flat_tree`, the chain is a real `TagNode::FlatList`;
+    /// otherwise tags are nested as: [tag1: [tag2: [tag3: item]]] - every
+    /// chain always ends in the `item` keyword, even for zero or one
+    /// elements, so the evaluator can walk the chain and know unambiguously
+    /// where the last real element is instead of guessing whether a given
+    /// node is "one more link" or "the final element" (needed so
+    /// `evaluate_tag` can fold a list tag into a `Value::List` of its
+    /// elements).
+    /// `span` is the overall range the synthetic wrapper nodes are stamped
+    /// with - they don't correspond to any single bracket pair the user
+    /// wrote.
+    fn build_chain(&self, tags: Vec<TagNode>, span: (usize, usize)) -> TagNode {
+        if self.config.flat_tree {
+            TagNode::FlatList { items: tags, span }
         } else {
-            // Multiple items: build nested structure
-            // tags = [tag1, tag2, tag3]
-            // reverse â†’ [tag3, tag2, tag1]
-            // pop until empty, building: tag1: [tag2: [tag3: item]]
-            
-            tags.reverse();  // Reverse so we can pop in correct order
-            let mut list_node = tags.pop().unwrap();  // Start with last tag (becomes innermost)
-            
-            // Build the chain backwards
-            // while let Some(tag) = ... is pattern matching in a loop
-            // It pops, and if Some(tag), executes body; if None, exits loop
-            while let Some(tag) = tags.pop() {
-                list_node = TagNode::Composite {
-                    ltag: Box::new(tag),          // Current tag becomes ltag
-                    rtag: Box::new(list_node),    // Previous nesting becomes rtag
+            let mut chain = TagNode::Primitive(Primitive::new(PrimitiveKind::Keyword("item".to_string()), span));
+
+            // Fold from the last tag inward so the first tag ends up
+            // outermost, matching the [tag1: [tag2: [tag3: item]]] shape.
+            for tag in tags.into_iter().rev() {
+                chain = TagNode::Composite {
+                    ltag: Box::new(tag),
+                    rtag: Box::new(chain),
+                    span,
                 };
             }
-            
-            // Wrap the whole thing in [list: ...]
-            TagNode::Composite {
-                ltag: Box::new(TagNode::Primitive(Primitive::Keyword("list".to_string()))),
-                rtag: Box::new(list_node),
-            }
+            chain
         }
     }
 
     /// Parse a single tag: [ltag: rtag]
     /// This is the core recursive parsing logic
     /// Uses the tag_stack to handle nested tags
-    fn parse_one_tag(&mut self) -> Result<TagNode, String> {
-        // Expect an opening bracket and consume it
-        self.expect_open_bracket()?;
+    ///
+    /// Never fails outright: a broken tag becomes a `TagNode::Error`
+    /// placeholder (via `recover`/`record_error`) with the failure pushed to
+    /// `self.diagnostics`, and parsing keeps going around it instead of
+    /// bailing on the first mistake.
+    fn parse_one_tag(&mut self) -> TagNode {
+        // The `[` we're about to consume marks where this tag starts
+        let start = self.current_span().offset;
+        if let Err(node) = self.expect_open_bracket(start) {
+            return node;
+        }
         // Push a new tag on the stack to track what we're parsing
-        self.tag_stack.push(TagInProgress::new());
+        self.tag_stack.push(TagInProgress::new(start));
 
         // Main parsing loop - continues until tag is complete (we see ])
         loop {
             match self.current() {
                 // Nested tag: recursively parse it
                 Token::OpenBracket => {
-                    let nested = self.parse_one_tag()?;  // Recursive call
+                    let nested = self.parse_one_tag();  // Recursive call
                     // Get the current (top) tag on the stack
-                    let current_tag = self.tag_stack.last_mut().ok_or("Tag stack empty")?;
+                    let stack_span = self.current_span();
+                    let current_tag = match self.tag_stack.last_mut() {
+                        Some(tag) => tag,
+                        None => return self.recover((stack_span.offset, stack_span.end), "Tag stack empty"),
+                    };
 
                     // Depending on which part we're parsing, store the nested tag
                     match current_tag.state {
@@ -221,37 +473,69 @@ impl StreamingParser {
                         }
                     }
                 }
-                
+
                 // End of this tag
                 Token::CloseBracket => {
+                    let end = self.current_span().end;
                     self.advance();
-                    let tag = self.tag_stack.pop().ok_or("Tag stack empty")?;
+                    let tag = match self.tag_stack.pop() {
+                        Some(tag) => tag,
+                        None => return self.record_error((end, end), "Tag stack empty"),
+                    };
+                    let tag_start = tag.start;
                     // Verify we have both parts before completing
                     if tag.is_complete() {
-                        return tag.to_composite();
+                        return tag
+                            .to_composite(end)
+                            .unwrap_or_else(|msg| self.record_error((tag_start, end), msg));
                     } else {
-                        return Err("Incomplete tag".to_string());
+                        return self.record_error((tag_start, end), "Incomplete tag");
                     }
                 }
-                
+
                 // Switch from ltag parsing to rtag parsing
                 Token::Colon => {
                     self.advance();
-                    let current_tag = self.tag_stack.last_mut().ok_or("Tag stack empty")?;
+                    let stack_span = self.current_span();
+                    let current_tag = match self.tag_stack.last_mut() {
+                        Some(tag) => tag,
+                        None => return self.recover((stack_span.offset, stack_span.end), "Tag stack empty"),
+                    };
                     current_tag.state = TagParseState::ParsingRTag;
                 }
-                
+
                 // Commas are ignored (they're just separators in lists)
                 Token::Comma => {
                     self.advance();
                 }
-                
+
                 // Identifier (variable name, operation name)
                 Token::Identifier(name) => {
                     let name = name.clone();
+                    let span = self.current_span();
                     self.advance();
-                    let primitive = TagNode::Primitive(Primitive::Identifier(name));
-                    let current_tag = self.tag_stack.last_mut().ok_or("Tag stack empty")?;
+
+                    // `pattern*` as the very first token of a tag (nothing
+                    // parsed into its ltag yet) marks a repetition:
+                    // `[pattern*: [elem, elem, ...]]` expands to the same
+                    // shape `create_list_node` builds, with `pattern` as the
+                    // wrapping ltag instead of the fixed `list` keyword.
+                    let is_repetition_head = self.current() == &Token::Star
+                        && self
+                            .tag_stack
+                            .last()
+                            .is_some_and(|tag| tag.state == TagParseState::ParsingLTag && tag.ltag.is_none());
+                    if is_repetition_head {
+                        self.advance(); // consume `*`
+                        return self.parse_repetition_tag(name, span.offset);
+                    }
+
+                    let primitive = TagNode::Primitive(Primitive::new(PrimitiveKind::Identifier(name), (span.offset, span.end)));
+                    let stack_span = self.current_span();
+                    let current_tag = match self.tag_stack.last_mut() {
+                        Some(tag) => tag,
+                        None => return self.recover((stack_span.offset, stack_span.end), "Tag stack empty"),
+                    };
 
                     // Store in appropriate side
                     match current_tag.state {
@@ -263,13 +547,18 @@ impl StreamingParser {
                         }
                     }
                 }
-                
+
                 // Number literal
                 Token::Number(n) => {
                     let num = *n;  // Dereference the reference to get the value
+                    let span = self.current_span();
                     self.advance();
-                    let primitive = TagNode::Primitive(Primitive::Number(num));
-                    let current_tag = self.tag_stack.last_mut().ok_or("Tag stack empty")?;
+                    let primitive = TagNode::Primitive(Primitive::new(PrimitiveKind::Number(num), (span.offset, span.end)));
+                    let stack_span = self.current_span();
+                    let current_tag = match self.tag_stack.last_mut() {
+                        Some(tag) => tag,
+                        None => return self.recover((stack_span.offset, stack_span.end), "Tag stack empty"),
+                    };
 
                     match current_tag.state {
                         TagParseState::ParsingLTag => {
@@ -280,13 +569,18 @@ impl StreamingParser {
                         }
                     }
                 }
-                
+
                 // String literal
                 Token::String(s) => {
                     let string = s.clone();
+                    let span = self.current_span();
                     self.advance();
-                    let primitive = TagNode::Primitive(Primitive::String(string));
-                    let current_tag = self.tag_stack.last_mut().ok_or("Tag stack empty")?;
+                    let primitive = TagNode::Primitive(Primitive::new(PrimitiveKind::String(string), (span.offset, span.end)));
+                    let stack_span = self.current_span();
+                    let current_tag = match self.tag_stack.last_mut() {
+                        Some(tag) => tag,
+                        None => return self.recover((stack_span.offset, stack_span.end), "Tag stack empty"),
+                    };
 
                     match current_tag.state {
                         TagParseState::ParsingLTag => {
@@ -297,13 +591,18 @@ impl StreamingParser {
                         }
                     }
                 }
-                
+
                 // Keyword (on, off, and, or, not, root, list, etc)
                 Token::Keyword(kw) => {
                     let keyword = kw.clone();
+                    let span = self.current_span();
                     self.advance();
-                    let primitive = TagNode::Primitive(Primitive::Keyword(keyword));
-                    let current_tag = self.tag_stack.last_mut().ok_or("Tag stack empty")?;
+                    let primitive = TagNode::Primitive(Primitive::new(PrimitiveKind::Keyword(keyword), (span.offset, span.end)));
+                    let stack_span = self.current_span();
+                    let current_tag = match self.tag_stack.last_mut() {
+                        Some(tag) => tag,
+                        None => return self.recover((stack_span.offset, stack_span.end), "Tag stack empty"),
+                    };
 
                     match current_tag.state {
                         TagParseState::ParsingLTag => {
@@ -314,23 +613,292 @@ impl StreamingParser {
                         }
                     }
                 }
-                
+
                 // Any other token is unexpected here
                 _ => {
-                    return Err(format!("Unexpected token: {:?}", self.current()));
+                    let found = format!("{:?}", self.current());
+                    let span = self.current_span();
+                    return self.recover((span.offset, span.end), format!("Unexpected token: {}", found));
+                }
+            }
+        }
+    }
+
+    /// Finish parsing a `[pattern*: [elem, elem, ...]]` repetition tag once
+    /// `pattern` (the identifier immediately before the `*`) and the `*`
+    /// itself have already been consumed - called from `parse_one_tag`'s
+    /// `Identifier` arm in place of its usual ltag handling, since a
+    /// repetition never fills in ltag/rtag the normal way. Pops the
+    /// in-progress tag this identifier was read for (it's being abandoned,
+    /// not completed) and expects exactly `: [` before handing off to
+    /// `match_repetition` for the element list, then the tag's own closing
+    /// `]`.
+    fn parse_repetition_tag(&mut self, pattern: String, tag_start: usize) -> TagNode {
+        self.tag_stack.pop();
+
+        if self.current() != &Token::Colon {
+            let found = format!("{:?}", self.current());
+            let span = self.current_span();
+            return self.recover((span.offset, span.end), format!("Expected ':' after '{}*', got {}", pattern, found));
+        }
+        self.advance();
+
+        if self.current() != &Token::OpenBracket {
+            let found = format!("{:?}", self.current());
+            let span = self.current_span();
+            return self.recover((span.offset, span.end), format!("Expected '[' to start '{}*' repetition, got {}", pattern, found));
+        }
+        self.advance();
+
+        let elements = match self.match_repetition() {
+            Ok(elements) => elements,
+            Err(node) => return node,
+        };
+
+        if self.current() != &Token::CloseBracket {
+            let found = format!("{:?}", self.current());
+            let span = self.current_span();
+            return self.recover((span.offset, span.end), format!("Expected ']' to close '{}*' tag, got {}", pattern, found));
+        }
+        let end = self.current_span().end;
+        self.advance();
+
+        let span = (tag_start, end);
+        TagNode::Composite {
+            ltag: Box::new(TagNode::Primitive(Primitive::new(PrimitiveKind::Identifier(pattern), span))),
+            rtag: Box::new(self.build_chain(elements, span)),
+            span,
+        }
+    }
+
+    /// Match the Kleene-star sub-pattern `elem (, elem)*` inside a
+    /// repetition's `[...]`, just past the opening bracket `parse_repetition_tag`
+    /// already consumed. A single `RepetitionCursor` tracks whether the next
+    /// token should start an element or continue/close the list -
+    /// `BeforeElement` accepts both `]` (the empty repetition) and an
+    /// element token, `AfterElement` accepts `,` or `]`. Returns `Err`
+    /// (already recorded via `recover`) on anything else, e.g. a `,` with
+    /// no element before it (a leading or doubled `,`), or two elements with
+    /// no `,` between them. A trailing `,` just before `]` is accepted.
+    fn match_repetition(&mut self) -> Result<Vec<TagNode>, TagNode> {
+        let mut cursor = RepetitionCursor::BeforeElement;
+        let mut bindings = Vec::new();
+
+        loop {
+            match self.current() {
+                Token::CloseBracket => {
+                    self.advance();
+                    return Ok(bindings);
+                }
+
+                Token::Comma => {
+                    self.advance();
+                    if cursor != RepetitionCursor::AfterElement {
+                        let span = self.current_span();
+                        return Err(self.recover((span.offset, span.end), "Unexpected ',' in repetition pattern"));
+                    }
+                    cursor = RepetitionCursor::BeforeElement;
+                }
+
+                _ => {
+                    // Any other token starts a new element: only valid if
+                    // we're not already expecting a `,` first.
+                    if cursor != RepetitionCursor::BeforeElement {
+                        let found = format!("{:?}", self.current());
+                        let span = self.current_span();
+                        return Err(self.recover((span.offset, span.end), format!("Expected ',' or ']' in repetition pattern, got {}", found)));
+                    }
+                    bindings.push(self.parse_element());
+                    cursor = RepetitionCursor::AfterElement;
                 }
             }
         }
     }
 
-    /// Helper: verify we have an opening bracket at current position
-    /// Consumes the bracket if found
-    fn expect_open_bracket(&mut self) -> Result<(), String> {
+    /// Parse exactly one repetition element: a primitive literal, or a
+    /// nested `[...]` tag (recursing through `parse_one_tag` same as any
+    /// other nested tag). Unlike the main `parse_one_tag` loop, there's no
+    /// ltag/rtag slot to fill - the caller collects the returned `TagNode`
+    /// directly into the repetition's bindings.
+    fn parse_element(&mut self) -> TagNode {
+        match self.current().clone() {
+            Token::OpenBracket => self.parse_one_tag(),
+            Token::Identifier(name) => {
+                let span = self.current_span();
+                self.advance();
+                TagNode::Primitive(Primitive::new(PrimitiveKind::Identifier(name), (span.offset, span.end)))
+            }
+            Token::Number(n) => {
+                let span = self.current_span();
+                self.advance();
+                TagNode::Primitive(Primitive::new(PrimitiveKind::Number(n), (span.offset, span.end)))
+            }
+            Token::String(s) => {
+                let span = self.current_span();
+                self.advance();
+                TagNode::Primitive(Primitive::new(PrimitiveKind::String(s), (span.offset, span.end)))
+            }
+            Token::Keyword(kw) => {
+                let span = self.current_span();
+                self.advance();
+                TagNode::Primitive(Primitive::new(PrimitiveKind::Keyword(kw), (span.offset, span.end)))
+            }
+            other => {
+                let found = format!("{:?}", other);
+                let span = self.current_span();
+                self.recover((span.offset, span.end), format!("Expected a repetition element, got {}", found))
+            }
+        }
+    }
+
+    /// Helper: verify we have an opening bracket at current position.
+    /// Consumes the bracket if found; otherwise recovers at `start` and
+    /// hands back the placeholder node for `parse_one_tag` to return
+    /// directly.
+    fn expect_open_bracket(&mut self, start: usize) -> Result<(), TagNode> {
         if self.current() == &Token::OpenBracket {
             self.advance();
             Ok(())
         } else {
-            Err(format!("Expected [, got {:?}", self.current()))
+            let found = format!("{:?}", self.current());
+            let end = self.current_span().end;
+            Err(self.recover((start, end), format!("Expected [, got {}", found)))
+        }
+    }
+}
+
+impl StreamingParser<std::vec::IntoIter<(Token, Span)>> {
+    /// Create a parser over an already-materialized spanned token stream -
+    /// the common case (e.g. `lexer::tokenize`'s output), kept as its own
+    /// constructor so existing callers don't need to spell out `.into_iter()`
+    /// or the iterator's concrete type.
+    pub fn new(tokens: Vec<(Token, Span)>) -> Self {
+        Self::parser_from(tokens.into_iter())
+    }
+
+    /// Like `new`, but with a `ParserConfig` controlling the shape of the
+    /// top-level tags `parse` builds and accepts.
+    pub fn with_config(tokens: Vec<(Token, Span)>, config: ParserConfig) -> Self {
+        Self::parser_from_with_config(tokens.into_iter(), config)
+    }
+}
+
+impl<I: Iterator<Item = (Token, Span)>> Iterator for StreamingParser<I> {
+    type Item = TagNode;
+
+    /// Pull just enough tokens from the underlying iterator to produce one
+    /// top-level tag, suspending here - rather than draining to EOF - so
+    /// callers can stream huge or never-ending input (a REPL, a socket) a
+    /// tag at a time instead of materializing every tag up front. A broken
+    /// tag still yields a `TagNode::Error` placeholder rather than ending
+    /// the stream early - check `self.diagnostics` (via `parse`) to see
+    /// whether anything went wrong.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current() == &Token::Eof {
+            return None;
+        }
+        Some(self.parse_one_tag())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    /// Parse `source` as a single top-level tag (no `create_root` wrapping -
+    /// just what `parse_one_tag` built), for inspecting a `pattern*`
+    /// repetition's own shape directly.
+    fn parse_one(source: &str) -> (TagNode, Vec<Diagnostic>) {
+        let lex_result = tokenize(source);
+        let mut parser = StreamingParser::new(lex_result.tokens);
+        let tag = parser.next().expect("expected one top-level tag");
+        (tag, parser.diagnostics().to_vec())
+    }
+
+    /// Walk a `build_chain` result (`[elem1: [elem2: [... : item]]]`) back
+    /// into a flat `Vec`, the same terminator-spotting `collect_list_items`
+    /// in evaluator_v2.rs uses, so a test can assert on element count/shape
+    /// without duplicating the evaluator.
+    fn collect_chain(node: &TagNode) -> Vec<TagNode> {
+        if let TagNode::Primitive(prim) = node {
+            if matches!(&prim.kind, PrimitiveKind::Keyword(kw) if kw == "item") {
+                return Vec::new();
+            }
         }
+        match node {
+            TagNode::Composite { ltag, rtag, .. } => {
+                let mut rest = collect_chain(rtag);
+                rest.insert(0, (**ltag).clone());
+                rest
+            }
+            other => vec![other.clone()],
+        }
+    }
+
+    #[test]
+    fn repetition_with_zero_elements_matches_empty_chain() {
+        let (tag, diagnostics) = parse_one("[things*: []]");
+        assert!(diagnostics.is_empty());
+        match tag {
+            TagNode::Composite { ltag, rtag, .. } => {
+                assert!(matches!(*ltag, TagNode::Primitive(Primitive { kind: PrimitiveKind::Identifier(ref name), .. }) if name == "things"));
+                assert!(collect_chain(&rtag).is_empty());
+            }
+            other => panic!("expected a composite repetition tag, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn repetition_with_one_element_matches_single_binding() {
+        let (tag, diagnostics) = parse_one(r#"[things*: ["a"]]"#);
+        assert!(diagnostics.is_empty());
+        match tag {
+            TagNode::Composite { rtag, .. } => {
+                let elements = collect_chain(&rtag);
+                assert_eq!(elements.len(), 1);
+            }
+            other => panic!("expected a composite repetition tag, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn repetition_with_several_elements_matches_every_binding_in_order() {
+        let (tag, diagnostics) = parse_one(r#"[things*: ["a", "b", "c"]]"#);
+        assert!(diagnostics.is_empty());
+        match tag {
+            TagNode::Composite { rtag, .. } => {
+                let elements = collect_chain(&rtag);
+                assert_eq!(elements.len(), 3);
+                let texts: Vec<&str> = elements
+                    .iter()
+                    .map(|e| match e {
+                        TagNode::Primitive(Primitive { kind: PrimitiveKind::String(s), .. }) => s.as_str(),
+                        other => panic!("expected a string element, got {:?}", other),
+                    })
+                    .collect();
+                assert_eq!(texts, vec!["a", "b", "c"]);
+            }
+            other => panic!("expected a composite repetition tag, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn repetition_allows_a_trailing_comma() {
+        // The `BeforeElement` cursor (reached right after a `,`) is
+        // accepting at `]` too - same trailing-comma leniency as a
+        // macro_rules repetition.
+        let (tag, diagnostics) = parse_one(r#"[things*: ["a",]]"#);
+        assert!(diagnostics.is_empty());
+        match tag {
+            TagNode::Composite { rtag, .. } => assert_eq!(collect_chain(&rtag).len(), 1),
+            other => panic!("expected a composite repetition tag, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn repetition_missing_comma_between_elements_reports_a_diagnostic() {
+        let (_tag, diagnostics) = parse_one(r#"[things*: ["a" "b"]]"#);
+        assert!(!diagnostics.is_empty());
     }
 }
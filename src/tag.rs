@@ -1,4 +1,5 @@
 use std::fmt;
+use serde::Serialize;
 
 // ============================================================================
 // VALUE ENUM - Runtime values during execution
@@ -6,14 +7,40 @@ use std::fmt;
 // These are the actual values that exist during execution
 // They differ from Primitive (which is compile-time) in that they've been evaluated
 // In C++, this would be like a tagged union (std::variant<double, string, bool, ...>)
+//
+// `Serialize` is derived so `--emit-json` can dump the variable store
+// straight to JSON: serde's default externally-tagged representation turns
+// a variant like `Number(42.0)` into `{"Number": 42.0}`, which is exactly
+// the tagged-union shape we want without any `#[serde(...)]` annotations.
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Value {
     Number(f64),                    // Floating point number
     Text(String),                   // String value
     Flag(bool),                     // Boolean (on/off in Packard script)
     Item,                           // Placeholder/unit value (like "null" or "()" in other languages)
     Reference(String),              // Points to a variable/attribute name (used for assignment)
+    List(Vec<Value>),               // Ordered collection, built from a `list` tag's elements
+}
+
+impl Value {
+    /// Number of elements, for a `List`; `None` for every other variant
+    /// (there's no sensible "length" of a number or a flag).
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Value::List(items) => Some(items.len()),
+            _ => None,
+        }
+    }
+
+    /// The element at `index`, for a `List`; `None` out of bounds or for
+    /// every other variant.
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        match self {
+            Value::List(items) => items.get(index),
+            _ => None,
+        }
+    }
 }
 
 // Implementing fmt::Display allows Value to be printed with {} (like operator<< in C++)
@@ -42,6 +69,18 @@ impl fmt::Display for Value {
             Value::Item => write!(f, "item"),
             // Reference: print with & prefix to show it's a reference
             Value::Reference(name) => write!(f, "&{}", name),
+            // List: bracketed, comma-separated elements, each using its own
+            // Display impl (so a nested list prints recursively too)
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
         }
     }
 }
@@ -55,11 +94,11 @@ impl fmt::Display for Value {
 // Example: [[set: [attribute: name]]: [text: Alice]]
 //          becomes a tree of TagNodes
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum TagNode {
     /// Composite tag: [ltag: rtag]
     /// Both sides can be primitives or further composites (recursive)
-    /// Example: Composite { 
+    /// Example: Composite {
     ///     ltag: Primitive(Keyword("set")),
     ///     rtag: Composite { ... }
     /// }
@@ -70,21 +109,70 @@ pub enum TagNode {
         // Box gives us a pointer of fixed size (8 bytes on 64-bit)
         ltag: Box<TagNode>,
         rtag: Box<TagNode>,
+        /// `(start, end)` char-offset range covering this tag's `[` through
+        /// its matching `]`, so a parse or evaluation failure here can point
+        /// a `Diagnostic` at the exact source text.
+        span: (usize, usize),
     },
     /// Primitive value: a leaf node (doesn't contain other tags)
     /// Like "name", 42, "hello", or "on"
     Primitive(Primitive),
+    /// A placeholder left by the parser's recovery mode where a tag
+    /// couldn't be built - an unexpected token, a missing `[`, an
+    /// incomplete `[ltag: rtag]`, etc. The parser still resynchronizes and
+    /// keeps going, so the rest of the tree is usable; `message` is a copy
+    /// of the `Diagnostic` pushed to the parser's diagnostics list for the
+    /// same failure.
+    Error {
+        span: (usize, usize),
+        message: String,
+    },
+    /// The top-level tags as a real `Vec<TagNode>`, built instead of the
+    /// right-nested `[tag1: [tag2: [tag3: item]]]` cons chain when the
+    /// parser is configured with `ParserConfig::flat_tree` - easier for
+    /// consumers that just want to iterate siblings without walking a
+    /// chain looking for the `item` terminator. The same option also builds
+    /// a `[pattern*: [...]]` repetition's elements as a `FlatList`.
+    FlatList {
+        items: Vec<TagNode>,
+        span: (usize, usize),
+    },
+}
+
+impl TagNode {
+    /// The `(start, end)` char-offset range this node came from - the
+    /// bracket pair for a `Composite`, the literal token for a `Primitive`,
+    /// the recovered range for an `Error` placeholder, or the overall range
+    /// for a `FlatList`.
+    pub fn span(&self) -> (usize, usize) {
+        match self {
+            TagNode::Composite { span, .. } => *span,
+            TagNode::Primitive(prim) => prim.span,
+            TagNode::Error { span, .. } => *span,
+            TagNode::FlatList { span, .. } => *span,
+        }
+    }
 }
 
 // ============================================================================
-// PRIMITIVE ENUM - Static tokens from the parser
+// PRIMITIVE - Static tokens from the parser
 // ============================================================================
 // These come directly from the lexer (Token types)
 // They haven't been evaluated yet (unlike Value which is the result of evaluation)
 // Primitive -> to_value() -> Value (evaluated result)
 
-#[derive(Debug, Clone)]
-pub enum Primitive {
+/// A literal value as the parser saw it, paired with the source span it was
+/// read from. The span lets a failure that bottoms out at a primitive (e.g.
+/// "operation name must be text") point at the exact token instead of just
+/// naming it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Primitive {
+    pub kind: PrimitiveKind,
+    pub span: (usize, usize),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum PrimitiveKind {
     Identifier(String),    // Variable/operation name: "set", "name", "myvar"
     Number(f64),          // Numeric literal: 42, 3.14
     String(String),       // String literal: "hello world"
@@ -92,20 +180,24 @@ pub enum Primitive {
 }
 
 impl Primitive {
+    pub fn new(kind: PrimitiveKind, span: (usize, usize)) -> Self {
+        Primitive { kind, span }
+    }
+
     /// Convert a static Primitive to a runtime Value
     /// This happens during evaluation - we transform the parsed structure into actual values
     /// Example: Primitive::Keyword("on") -> Value::Flag(true)
     pub fn to_value(&self) -> Value {
-        match self {
+        match &self.kind {
             // Identifier: treat as text (could be a variable name or operation)
-            Primitive::Identifier(s) => Value::Text(s.clone()),
+            PrimitiveKind::Identifier(s) => Value::Text(s.clone()),
             // Number: directly convert to Value::Number
             // The * dereferences the &f64 to get the actual value
-            Primitive::Number(n) => Value::Number(*n),
+            PrimitiveKind::Number(n) => Value::Number(*n),
             // String: treat as text (the quotes are semantic, not part of the value)
-            Primitive::String(s) => Value::Text(s.clone()),
+            PrimitiveKind::String(s) => Value::Text(s.clone()),
             // Keyword: special handling for "on" and "off"
-            Primitive::Keyword(kw) => match kw.as_str() {
+            PrimitiveKind::Keyword(kw) => match kw.as_str() {
                 "on" => Value::Flag(true),      // Packard script boolean true
                 "off" => Value::Flag(false),    // Packard script boolean false
                 _ => Value::Text(kw.clone()),   // Other keywords are just text
@@ -117,9 +209,9 @@ impl Primitive {
     /// Used for printing the parse tree (like when you see the output of format_tag)
     /// This shows what was in the source, with appropriate formatting
     pub fn as_display_string(&self) -> String {
-        match self {
-            Primitive::Identifier(s) => s.clone(),
-            Primitive::Number(n) => {
+        match &self.kind {
+            PrimitiveKind::Identifier(s) => s.clone(),
+            PrimitiveKind::Number(n) => {
                 // Print whole numbers without decimals for readability
                 if n.fract() == 0.0 {
                     format!("{}", *n as i64)
@@ -128,9 +220,9 @@ impl Primitive {
                 }
             }
             // Strings: add quotes back for display
-            Primitive::String(s) => format!("\"{}\"", s),
+            PrimitiveKind::String(s) => format!("\"{}\"", s),
             // Keywords: just the keyword itself
-            Primitive::Keyword(s) => s.clone(),
+            PrimitiveKind::Keyword(s) => s.clone(),
         }
     }
 
@@ -138,12 +230,12 @@ impl Primitive {
     /// Returns Option<String> - Some if it's text-like, None if it's a number
     /// This is used to get operation names (which must be textual)
     pub fn as_text(&self) -> Option<String> {
-        match self {
-            Primitive::Identifier(s) => Some(s.clone()),
-            Primitive::String(s) => Some(s.clone()),
-            Primitive::Keyword(kw) => Some(kw.clone()),
+        match &self.kind {
+            PrimitiveKind::Identifier(s) => Some(s.clone()),
+            PrimitiveKind::String(s) => Some(s.clone()),
+            PrimitiveKind::Keyword(kw) => Some(kw.clone()),
             // Numbers have no text representation - return None
-            Primitive::Number(_) => None,
+            PrimitiveKind::Number(_) => None,
         }
     }
 }
@@ -156,13 +248,15 @@ impl TagNode {
     pub fn evaluate_ltag(&self) -> Result<Value, String> {
         match self {
             TagNode::Primitive(prim) => Ok(prim.to_value()),
-            TagNode::Composite { ltag, rtag } => {
+            TagNode::Composite { ltag, rtag, .. } => {
                 // Note: the underscore prefix (_) tells Rust we're intentionally not using these
                 let _ltag_val = ltag.evaluate_ltag()?;
                 let _rtag_val = rtag.evaluate_ltag()?;
                 // For now, just return a placeholder
                 Ok(Value::Item)
             }
+            TagNode::Error { message, .. } => Err(message.clone()),
+            TagNode::FlatList { .. } => Ok(Value::Item),
         }
     }
 
@@ -172,12 +266,14 @@ impl TagNode {
     pub fn evaluate_rtag(&self) -> Result<Value, String> {
         match self {
             TagNode::Primitive(prim) => Ok(prim.to_value()),
-            TagNode::Composite { ltag, rtag } => {
+            TagNode::Composite { ltag, rtag, .. } => {
                 let _ltag_val = ltag.evaluate_rtag()?;
                 let _rtag_val = rtag.evaluate_rtag()?;
                 // For now, just return a placeholder
                 Ok(Value::Item)
             }
+            TagNode::Error { message, .. } => Err(message.clone()),
+            TagNode::FlatList { .. } => Ok(Value::Item),
         }
     }
 
@@ -188,10 +284,15 @@ impl TagNode {
     pub fn to_display_string(&self) -> String {
         match self {
             TagNode::Primitive(prim) => prim.as_display_string(),
-            TagNode::Composite { ltag, rtag } => {
+            TagNode::Composite { ltag, rtag, .. } => {
                 // Recursively format both sides with [ltag: rtag] structure
                 format!("[{}: {}]", ltag.to_display_string(), rtag.to_display_string())
             }
+            TagNode::Error { message, .. } => format!("<error: {}>", message),
+            TagNode::FlatList { items, .. } => {
+                let rendered: Vec<String> = items.iter().map(|t| t.to_display_string()).collect();
+                format!("[{}]", rendered.join(", "))
+            }
         }
     }
 }